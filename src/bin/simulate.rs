@@ -0,0 +1,332 @@
+//! Runs the Compare simulations without a window and prints the curves.
+//!
+//! ```text
+//! simulate --function damper-exact --param 0.2 --fps 60,15 --duration 2 --format json
+//! simulate --function smooth-damp --replay session.csv --format svg > replay.svg
+//! ```
+
+use std::fmt::Write;
+use std::process::ExitCode;
+
+use spring_it_on::recording::{replay, Recording};
+use spring_it_on::simulation::{reference, simulate, Curve, Model};
+use spring_it_on::smoothing::Function;
+use spring_it_on::svg::{Plot, Stroke};
+
+const USAGE: &str = "\
+Usage: simulate [options]
+
+Options:
+  --function <name>   exact, lerp, damper-bad, damper-exact, damper-exact2, smooth-damp
+                      (default: lerp)
+  --param <value>     factor, damper, half life, rate or smooth time of the function
+  --max-speed <value> speed limit for smooth-damp
+  --fps <list>        comma separated framerates (default: 60,15)
+  --duration <secs>   simulated time (default: 2)
+  --start <value>     value at rest at the start (default: 0)
+  --goal <value>      value to move towards (default: 1)
+  --reference         also print the continuous-time reference
+  --replay <path>     run the function against a session recorded in the playground
+                      instead of the framerates, and print the recorded goal with it
+  --format <format>   csv, json or svg (default: csv)
+  -h, --help          print this message";
+
+/// Same colours as the playground, which this binary does not link against.
+const PALETTE: [[f32; 4]; 6] = [
+    [0.0, 0.47, 0.95, 1.0],
+    [1.0, 0.63, 0.0, 1.0],
+    [0.0, 0.89, 0.19, 1.0],
+    [1.0, 0.43, 0.76, 1.0],
+    [0.99, 0.98, 0.0, 1.0],
+    [0.53, 0.24, 0.75, 1.0],
+];
+
+enum Format {
+    Csv,
+    Json,
+    Svg,
+}
+
+struct Options {
+    function: Function,
+    frame_rates: Vec<f32>,
+    duration: f32,
+    start: f32,
+    goal: f32,
+    reference: bool,
+    replay: Option<String>,
+    format: Format,
+}
+
+/// One printed curve.
+struct Series {
+    label: String,
+    frame_rate: Option<f32>,
+    /// One sample per frame, as opposed to references and goals.
+    stepped: bool,
+    curve: Curve,
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<f32, String> {
+    let number: f32 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{flag}: '{value}' is not a number"))?;
+    if !number.is_finite() {
+        return Err(format!("{flag}: '{value}' is not a finite number"));
+    }
+    Ok(number)
+}
+
+fn parse_positive(flag: &str, value: &str) -> Result<f32, String> {
+    let number = parse_number(flag, value)?;
+    if number <= 0.0 {
+        return Err(format!("{flag}: '{value}' must be positive"));
+    }
+    Ok(number)
+}
+
+fn function(name: &str, param: Option<f32>, max_speed: Option<f32>) -> Result<Function, String> {
+    Ok(match name {
+        "exact" => Function::Exact,
+        "lerp" => Function::Lerp {
+            factor: param.unwrap_or(0.5),
+        },
+        "damper-bad" => Function::DamperBad {
+            damper: param.unwrap_or(5.0),
+        },
+        "damper-exact" => Function::DamperExact {
+            half_life: param.unwrap_or(1.0),
+        },
+        "damper-exact2" => Function::DamperExact2 {
+            rate: param.unwrap_or(1.0),
+        },
+        "smooth-damp" => Function::SmoothDamp {
+            smooth_time: param.unwrap_or(0.3),
+            max_speed,
+        },
+        _ => return Err(format!("unknown function '{name}'")),
+    })
+}
+
+/// Returns `None` when help was requested.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut name = "lerp".to_string();
+    let mut param = None;
+    let mut max_speed = None;
+    let mut options = Options {
+        function: Function::default(),
+        frame_rates: vec![60.0, 15.0],
+        duration: 2.0,
+        start: 0.0,
+        goal: 1.0,
+        reference: false,
+        replay: None,
+        format: Format::Csv,
+    };
+
+    while let Some(flag) = args.next() {
+        if flag == "-h" || flag == "--help" {
+            return Ok(None);
+        }
+        if flag == "--reference" {
+            options.reference = true;
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{flag} expects a value"))?;
+        match flag.as_str() {
+            "--function" => name = value,
+            "--param" => param = Some(parse_number(&flag, &value)?),
+            "--max-speed" => max_speed = Some(parse_number(&flag, &value)?),
+            "--fps" => {
+                options.frame_rates = value
+                    .split(',')
+                    .map(|rate| parse_positive(&flag, rate))
+                    .collect::<Result<_, _>>()?;
+            }
+            "--duration" => options.duration = parse_positive(&flag, &value)?,
+            "--start" => options.start = parse_number(&flag, &value)?,
+            "--goal" => options.goal = parse_number(&flag, &value)?,
+            "--replay" => options.replay = Some(value),
+            "--format" => {
+                options.format = match value.as_str() {
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    "svg" => Format::Svg,
+                    _ => return Err(format!("{flag}: unknown format '{value}'")),
+                }
+            }
+            _ => return Err(format!("unknown option '{flag}'")),
+        }
+    }
+    options.function = function(&name, param, max_speed)?;
+    Ok(Some(options))
+}
+
+/// One row per sample: `series,frame_rate,time,value`. Curves without a fixed framerate leave
+/// it empty.
+fn write_csv(curves: &[Series]) -> String {
+    let mut out = String::from("series,frame_rate,time,value\n");
+    for series in curves {
+        let label = &series.label;
+        let frame_rate = series
+            .frame_rate
+            .map(|rate| rate.to_string())
+            .unwrap_or_default();
+        let curve = &series.curve;
+        for (time, value) in curve.times.iter().zip(&curve.values) {
+            writeln!(out, "\"{label}\",{frame_rate},{time},{value}").unwrap();
+        }
+    }
+    out
+}
+
+/// JSON has no NaN or infinity, a model that blew up shows as `null`.
+fn json_numbers(values: &[f32]) -> String {
+    let values: Vec<String> = values
+        .iter()
+        .map(|value| match value.is_finite() {
+            true => value.to_string(),
+            false => "null".to_string(),
+        })
+        .collect();
+    format!("[{}]", values.join(","))
+}
+
+fn write_json(curves: &[Series]) -> String {
+    let series: Vec<String> = curves
+        .iter()
+        .map(|series| {
+            let frame_rate = series
+                .frame_rate
+                .map_or("null".to_string(), |rate| rate.to_string());
+            format!(
+                "{{\"label\":\"{}\",\"frame_rate\":{frame_rate},\"times\":{},\"values\":{}}}",
+                series.label.replace('\\', "\\\\").replace('"', "\\\""),
+                json_numbers(&series.curve.times),
+                json_numbers(&series.curve.values),
+            )
+        })
+        .collect();
+    format!("{{\"series\":[{}]}}\n", series.join(","))
+}
+
+fn write_svg(curves: &[Series]) -> String {
+    let mut plot = Plot::new(800.0, 500.0);
+    for (idx, series) in curves.iter().enumerate() {
+        let stroke = if series.stepped {
+            Stroke::FRAMES
+        } else {
+            Stroke::REFERENCE
+        };
+        let color = PALETTE[idx % PALETTE.len()];
+        plot.curve(&series.label, color, stroke, &series.curve);
+    }
+    plot.render()
+}
+
+fn print(format: &Format, curves: &[Series]) -> ExitCode {
+    match format {
+        Format::Csv => print!("{}", write_csv(curves)),
+        Format::Json => print!("{}", write_json(curves)),
+        Format::Svg => print!("{}", write_svg(curves)),
+    }
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let model = &options.function;
+    let mut curves = Vec::new();
+    if let Some(path) = &options.replay {
+        let recording = match std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| Recording::parse(&text))
+        {
+            Ok(recording) => recording,
+            Err(err) => {
+                eprintln!("error: {path}: {err}");
+                return ExitCode::FAILURE;
+            }
+        };
+        let curve = replay(model, &recording);
+        let goal = Curve {
+            times: curve.times.clone(),
+            values: std::iter::once(recording.start)
+                .chain(recording.frames.iter().map(|frame| frame.goal))
+                .collect(),
+        };
+        curves.push(Series {
+            label: "goal".to_string(),
+            frame_rate: None,
+            stepped: false,
+            curve: goal,
+        });
+        curves.push(Series {
+            label: format!("{} @ replay", model.label()),
+            frame_rate: None,
+            stepped: true,
+            curve,
+        });
+        return print(&options.format, &curves);
+    }
+    if options.reference {
+        let curve = reference(model, options.duration, options.start, options.goal);
+        curves.push(Series {
+            label: format!("{} reference", model.label()),
+            frame_rate: None,
+            stepped: false,
+            curve,
+        });
+    }
+    for &frame_rate in &options.frame_rates {
+        let frame_times = std::iter::repeat(1.0 / frame_rate);
+        let curve = simulate(
+            model,
+            options.duration,
+            frame_times,
+            options.start,
+            options.goal,
+        );
+        let label = format!("{} @ {frame_rate:.0} fps", model.label());
+        curves.push(Series {
+            label,
+            frame_rate: Some(frame_rate),
+            stepped: true,
+            curve,
+        });
+    }
+    print(&options.format, &curves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_has_no_nan_or_infinity() {
+        let numbers = json_numbers(&[0.5, f32::NAN, f32::INFINITY, -1.0]);
+        assert_eq!(numbers, "[0.5,null,null,-1]");
+    }
+
+    #[test]
+    fn rejects_endless_inputs() {
+        for args in [["--fps", "inf"], ["--fps", "nan"], ["--duration", "inf"]] {
+            let args = args.into_iter().map(str::to_string);
+            assert!(parse_args(args).is_err());
+        }
+    }
+}
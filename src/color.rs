@@ -0,0 +1,246 @@
+use glam::{Mat3, Vec3, Vec4};
+
+/// Where colours are smoothed. Colours go in and come out as sRGB with straight alpha in
+/// `0.0..=1.0`, alpha is always smoothed as it is.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorSpace {
+    /// The channels as stored, which darkens and muddies the middle of a fade.
+    Srgb,
+    LinearRgb,
+    /// Perceptually uniform, see <https://bottosson.github.io/posts/oklab/>.
+    Oklab,
+    /// Hue in turns, going the short way round the colour wheel.
+    Hsv,
+}
+
+impl ColorSpace {
+    pub const ALL: [ColorSpace; 4] = [
+        ColorSpace::Srgb,
+        ColorSpace::LinearRgb,
+        ColorSpace::Oklab,
+        ColorSpace::Hsv,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            ColorSpace::Srgb => "sRGB",
+            ColorSpace::LinearRgb => "Linear RGB",
+            ColorSpace::Oklab => "Oklab",
+            ColorSpace::Hsv => "HSV",
+        }
+    }
+
+    pub fn from_srgb(self, color: Vec4) -> Vec4 {
+        let rgb = color.truncate();
+        let converted = match self {
+            ColorSpace::Srgb => rgb,
+            ColorSpace::LinearRgb => each(rgb, srgb_to_linear),
+            ColorSpace::Oklab => linear_to_oklab(each(rgb, srgb_to_linear)),
+            ColorSpace::Hsv => rgb_to_hsv(rgb),
+        };
+        converted.extend(color.w)
+    }
+
+    /// Inverse of [`ColorSpace::from_srgb`], clamped back into the sRGB gamut.
+    pub fn to_srgb(self, color: Vec4) -> Vec4 {
+        let values = color.truncate();
+        let rgb = match self {
+            ColorSpace::Srgb => values,
+            ColorSpace::LinearRgb => each(values, linear_to_srgb),
+            ColorSpace::Oklab => each(oklab_to_linear(values), linear_to_srgb),
+            ColorSpace::Hsv => hsv_to_rgb(values),
+        };
+        rgb.clamp(Vec3::ZERO, Vec3::ONE).extend(color.w)
+    }
+
+    /// `to` moved by whole turns of hue to be at most half a turn away from `from`, both in
+    /// this space. Other spaces have nothing to wrap.
+    pub fn nearest(self, from: Vec4, to: Vec4) -> Vec4 {
+        match self {
+            ColorSpace::Hsv => {
+                let hue = from.x + ((to.x - from.x + 0.5).rem_euclid(1.0) - 0.5);
+                Vec4::new(hue, to.y, to.z, to.w)
+            }
+            _ => to,
+        }
+    }
+
+    /// Brings a smoothed colour back into the canonical range of this space.
+    pub fn wrap(self, color: Vec4) -> Vec4 {
+        match self {
+            ColorSpace::Hsv => Vec4::new(color.x.rem_euclid(1.0), color.y, color.z, color.w),
+            _ => color,
+        }
+    }
+}
+
+fn each(values: Vec3, f: fn(f32) -> f32) -> Vec3 {
+    Vec3::from(values.to_array().map(f))
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    let c = c.max(0.0);
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// From the Oklab post, column major: every three numbers make up one column.
+const LINEAR_TO_LMS: Mat3 = Mat3::from_cols_array(&[
+    0.412_221_46,
+    0.211_903_5,
+    0.088_302_46,
+    0.536_332_55,
+    0.680_699_5,
+    0.281_718_85,
+    0.051_445_995,
+    0.107_396_96,
+    0.629_978_7,
+]);
+
+const LMS_TO_OKLAB: Mat3 = Mat3::from_cols_array(&[
+    0.210_454_26,
+    1.977_998_5,
+    0.025_904_037,
+    0.793_617_8,
+    -2.428_592_2,
+    0.782_771_77,
+    -0.004_072_047,
+    0.450_593_7,
+    -0.808_675_77,
+]);
+
+const OKLAB_TO_LMS: Mat3 = Mat3::from_cols_array(&[
+    1.0,
+    1.0,
+    1.0,
+    0.396_337_78,
+    -0.105_561_346,
+    -0.089_484_18,
+    0.215_803_76,
+    -0.063_854_17,
+    -1.291_485_5,
+]);
+
+const LMS_TO_LINEAR: Mat3 = Mat3::from_cols_array(&[
+    4.076_741_7,
+    -1.268_438,
+    -0.004_196_086_3,
+    -3.307_711_6,
+    2.609_757_4,
+    -0.703_418_6,
+    0.230_969_94,
+    -0.341_319_38,
+    1.707_614_7,
+]);
+
+fn linear_to_oklab(rgb: Vec3) -> Vec3 {
+    let lms = LINEAR_TO_LMS * rgb;
+    LMS_TO_OKLAB * each(lms, f32::cbrt)
+}
+
+fn oklab_to_linear(lab: Vec3) -> Vec3 {
+    let lms = OKLAB_TO_LMS * lab;
+    LMS_TO_LINEAR * (lms * lms * lms)
+}
+
+fn rgb_to_hsv(rgb: Vec3) -> Vec3 {
+    let max = rgb.max_element();
+    let min = rgb.min_element();
+    let chroma = max - min;
+    let hue = if chroma <= 0.0 {
+        0.0
+    } else if max == rgb.x {
+        ((rgb.y - rgb.z) / chroma).rem_euclid(6.0)
+    } else if max == rgb.y {
+        (rgb.z - rgb.x) / chroma + 2.0
+    } else {
+        (rgb.x - rgb.y) / chroma + 4.0
+    };
+    let saturation = if max <= 0.0 { 0.0 } else { chroma / max };
+    Vec3::new(hue / 6.0, saturation, max)
+}
+
+fn hsv_to_rgb(hsv: Vec3) -> Vec3 {
+    let hue = hsv.x.rem_euclid(1.0) * 6.0;
+    let chroma = hsv.z * hsv.y;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    Vec3::new(r, g, b) + (hsv.z - chroma)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: Vec4, expected: Vec4, tolerance: f32) {
+        assert!(
+            actual.abs_diff_eq(expected, tolerance),
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn converts_there_and_back() {
+        let levels = [0.0, 0.02, 0.25, 0.5, 0.8, 1.0];
+        for space in ColorSpace::ALL {
+            for r in levels {
+                for g in levels {
+                    for b in levels {
+                        let color = Vec4::new(r, g, b, 0.7);
+                        let there_and_back = space.to_srgb(space.from_srgb(color));
+                        assert_near(there_and_back, color, 1e-4);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn oklab_puts_white_at_full_lightness() {
+        let white = ColorSpace::Oklab.from_srgb(Vec4::ONE);
+        assert_near(white, Vec4::new(1.0, 0.0, 0.0, 1.0), 1e-3);
+        let black = ColorSpace::Oklab.from_srgb(Vec4::W);
+        assert_near(black, Vec4::W, 1e-6);
+    }
+
+    #[test]
+    fn hsv_hue_is_in_turns() {
+        let hue = |rgb: Vec3| ColorSpace::Hsv.from_srgb(rgb.extend(1.0)).x;
+        assert_eq!(hue(Vec3::X), 0.0);
+        assert!((hue(Vec3::Y) - 1.0 / 3.0).abs() < 1e-6);
+        assert!((hue(Vec3::Z) - 2.0 / 3.0).abs() < 1e-6);
+        assert!((hue(Vec3::new(1.0, 0.0, 0.5)) - 11.0 / 12.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn nearest_wraps_hue_across_zero() {
+        let hsv = |hue: f32| Vec4::new(hue, 1.0, 1.0, 1.0);
+        let space = ColorSpace::Hsv;
+        assert_near(space.nearest(hsv(0.95), hsv(0.05)), hsv(1.05), 1e-6);
+        assert_near(space.nearest(hsv(0.05), hsv(0.95)), hsv(-0.05), 1e-6);
+        assert_near(space.nearest(hsv(0.2), hsv(0.6)), hsv(0.6), 1e-6);
+        assert_near(space.wrap(hsv(1.05)), hsv(0.05), 1e-6);
+        assert_near(space.wrap(hsv(-0.05)), hsv(0.95), 1e-6);
+        let rgb = Vec4::new(0.95, 0.0, 0.05, 1.0);
+        assert_eq!(ColorSpace::Oklab.nearest(Vec4::ZERO, rgb), rgb);
+    }
+}
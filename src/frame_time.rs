@@ -0,0 +1,156 @@
+use crate::rng::Rng;
+
+/// How frame times vary around the nominal `1.0 / frame_rate`.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameProfile {
+    Constant,
+    /// Every frame is off by up to `amount` of the nominal frame time.
+    Jitter {
+        amount: f32,
+    },
+    /// Every `period`-th frame takes `factor` times as long.
+    Spikes {
+        period: u32,
+        factor: f32,
+    },
+    /// Any frame takes `factor` times as long with probability `chance`.
+    Hitches {
+        chance: f32,
+        factor: f32,
+    },
+}
+
+impl FrameProfile {
+    pub const ALL: [FrameProfile; 4] = [
+        FrameProfile::Constant,
+        FrameProfile::Jitter { amount: 0.3 },
+        FrameProfile::Spikes {
+            period: 10,
+            factor: 4.0,
+        },
+        FrameProfile::Hitches {
+            chance: 0.05,
+            factor: 6.0,
+        },
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            FrameProfile::Constant => "Constant",
+            FrameProfile::Jitter { .. } => "Uniform jitter",
+            FrameProfile::Spikes { .. } => "Periodic spikes",
+            FrameProfile::Hitches { .. } => "Random hitches",
+        }
+    }
+
+    /// Endless sequence of frame times around `1.0 / frame_rate`.
+    pub fn frame_times(&self, frame_rate: f32, seed: u64) -> impl Iterator<Item = f32> {
+        let profile = *self;
+        let nominal = 1.0 / frame_rate;
+        let mut rng = Rng::new(seed);
+        (1u32..).map(move |frame| match profile {
+            FrameProfile::Constant => nominal,
+            FrameProfile::Jitter { amount } => nominal * (1.0 + rng.range(-amount, amount)),
+            FrameProfile::Spikes { period, factor } => {
+                if frame % period.max(1) == 0 {
+                    nominal * factor
+                } else {
+                    nominal
+                }
+            }
+            FrameProfile::Hitches { chance, factor } => {
+                if rng.next_f32() < chance {
+                    nominal * factor
+                } else {
+                    nominal
+                }
+            }
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeUnit {
+    Seconds,
+    Milliseconds,
+}
+
+impl TimeUnit {
+    fn to_seconds(self, value: f32) -> f32 {
+        match self {
+            TimeUnit::Seconds => value,
+            TimeUnit::Milliseconds => value / 1000.0,
+        }
+    }
+}
+
+/// Parses a frame-time capture. Lines with a single number are frame times, lines with
+/// several columns start with a timestamp and frame times are the differences between them.
+/// Lines that don't start with a number (headers, comments) are skipped.
+pub fn parse_trace(text: &str, unit: TimeUnit) -> Result<Vec<f32>, String> {
+    let mut frame_times = Vec::new();
+    let mut last_timestamp = None;
+    for (line_number, line) in text.lines().enumerate() {
+        let mut columns = line
+            .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+            .filter(|column| !column.is_empty());
+        let Some(Ok(first)) = columns.next().map(str::parse::<f32>) else {
+            continue;
+        };
+        let first = unit.to_seconds(first);
+        let dt = if columns.next().is_some() {
+            let previous = last_timestamp.replace(first);
+            match previous {
+                Some(previous) => first - previous,
+                None => continue,
+            }
+        } else {
+            first
+        };
+        if !(dt > 0.0 && dt.is_finite()) {
+            return Err(format!(
+                "line {}: frame time must be positive, got {dt}",
+                line_number + 1
+            ));
+        }
+        frame_times.push(dt);
+    }
+    if frame_times.is_empty() {
+        return Err("no frame times found".to_string());
+    }
+    Ok(frame_times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_column_with_header() {
+        let frame_times = parse_trace("frame_time_ms\n16.5\n\n33.0\n", TimeUnit::Milliseconds);
+        assert_eq!(frame_times, Ok(vec![0.0165, 0.033]));
+    }
+
+    #[test]
+    fn parses_timestamps_as_differences() {
+        let text = "# time, fps\n1.0, 60\n1.5; 60\n2.25\t60\n";
+        assert_eq!(parse_trace(text, TimeUnit::Seconds), Ok(vec![0.5, 0.75]));
+    }
+
+    #[test]
+    fn rejects_non_positive_frame_times() {
+        let err = parse_trace("0.016\n-0.016\n", TimeUnit::Seconds).unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+        assert!(parse_trace("inf\n", TimeUnit::Seconds).is_err());
+        assert!(parse_trace("2.0, 1\n1.0, 1\n", TimeUnit::Seconds).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_traces() {
+        assert!(parse_trace("", TimeUnit::Seconds).is_err());
+        assert!(parse_trace("only a header\n", TimeUnit::Seconds).is_err());
+        assert!(parse_trace("5.0, 1\n", TimeUnit::Seconds).is_err());
+    }
+}
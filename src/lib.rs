@@ -0,0 +1,15 @@
+//! Smoothing functions, springs, PID controllers and spatial data structures from the
+//! playground, without any rendering.
+
+pub mod color;
+pub mod frame_time;
+pub mod permalink;
+pub mod pid;
+pub mod recording;
+pub mod rng;
+pub mod signal;
+pub mod simulation;
+pub mod smoothing;
+pub mod spatial;
+pub mod spring;
+pub mod svg;
@@ -0,0 +1,114 @@
+//! Packs named settings into a URL fragment (`#key=value&key=value`) and back.
+
+use std::collections::BTreeMap;
+
+/// Percent-encodes every byte except the URL-safe unreserved characters.
+fn escape(text: &str, out: &mut String) {
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut input = text.bytes();
+    while let Some(byte) = input.next() {
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let hex = [input.next(), input.next()];
+        let digits = match hex {
+            [Some(high), Some(low)] if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                std::str::from_utf8(&[high, low])
+                    .ok()
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+            }
+            _ => None,
+        };
+        bytes.push(digits.ok_or_else(|| format!("Invalid escape in '{text}'"))?);
+    }
+    String::from_utf8(bytes).map_err(|err| err.to_string())
+}
+
+/// Fragment without the leading `#`.
+pub fn encode<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut fragment = String::new();
+    for (key, value) in entries {
+        if !fragment.is_empty() {
+            fragment.push('&');
+        }
+        escape(key, &mut fragment);
+        fragment.push('=');
+        escape(value, &mut fragment);
+    }
+    fragment
+}
+
+/// Inverse of [`encode`]. A leading `#` is ignored.
+pub fn decode(fragment: &str) -> Result<BTreeMap<String, String>, String> {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    fragment
+        .split('&')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Missing '=' in '{entry}'"))?;
+            Ok((unescape(key)?, unescape(value)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(entries: &[(&str, &str)]) {
+        let fragment = encode(entries.iter().copied());
+        let decoded = decode(&format!("#{fragment}")).unwrap();
+        let expected: BTreeMap<String, String> = entries
+            .iter()
+            .map(|&(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        assert_eq!(decoded, expected, "{fragment}");
+    }
+
+    #[test]
+    fn round_trips_reserved_characters() {
+        round_trip(&[
+            ("tab", "Smoothing"),
+            ("a&b=c", "(fns:[Lerp(factor:0.5)],selected_index:0)"),
+            ("%#?/ +", "50% & more=#1?x/y+z"),
+        ]);
+        let fragment = encode([("a&b=c", "%#?/ +")]);
+        assert!(fragment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "%=-_.~".contains(c)));
+    }
+
+    #[test]
+    fn round_trips_multibyte_utf8() {
+        round_trip(&[("größe", "ÿ 日本 🦀"), ("", "")]);
+        assert_eq!(encode([("é", "")]), "%C3%A9=");
+    }
+
+    #[test]
+    fn decodes_empty_fragments() {
+        assert_eq!(decode(""), Ok(BTreeMap::new()));
+        assert_eq!(decode("#"), Ok(BTreeMap::new()));
+        assert_eq!(decode("#&&"), Ok(BTreeMap::new()));
+    }
+
+    #[test]
+    fn rejects_malformed_fragments() {
+        for fragment in ["key", "a=%", "a=%4", "a%2=b", "a=%zz", "a=%+1", "a=%C3"] {
+            assert!(decode(fragment).is_err(), "{fragment}");
+        }
+    }
+}
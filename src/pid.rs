@@ -0,0 +1,90 @@
+/// The system the controller is driving. Its output is `position`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Plant {
+    FirstOrderLag { time_constant: f32 },
+    PointMass { mass: f32, friction: f32 },
+    MassUnderGravity { mass: f32, gravity: f32 },
+}
+
+impl Plant {
+    /// Applies the controller output `input` for `dt` seconds.
+    fn step(&self, position: f32, velocity: f32, input: f32, dt: f32) -> (f32, f32) {
+        match self {
+            Plant::FirstOrderLag { time_constant } => {
+                let velocity = (input - position) / time_constant.max(1e-5);
+                (position + velocity * dt, velocity)
+            }
+            Plant::PointMass { mass, friction } => {
+                let velocity = velocity + (input - friction * velocity) / mass * dt;
+                (position + velocity * dt, velocity)
+            }
+            Plant::MassUnderGravity { mass, gravity } => {
+                let velocity = velocity + (input / mass + gravity) * dt;
+                (position + velocity * dt, velocity)
+            }
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Plant::FirstOrderLag { .. } => "First order lag",
+            Plant::PointMass { .. } => "Point mass",
+            Plant::MassUnderGravity { .. } => "Mass under gravity",
+        }
+    }
+}
+
+/// Controller memory together with the state of the plant it drives.
+#[derive(Default, Clone, Copy)]
+pub struct PidState {
+    pub position: f32,
+    pub velocity: f32,
+    integral: f32,
+    previous_error: Option<f32>,
+}
+
+impl PidState {
+    pub fn at(position: f32) -> Self {
+        Self {
+            position,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pid {
+    pub proportional: f32,
+    pub integral: f32,
+    pub derivative: f32,
+}
+
+impl Default for Pid {
+    fn default() -> Self {
+        Self {
+            proportional: 20.0,
+            integral: 0.0,
+            derivative: 5.0,
+        }
+    }
+}
+
+impl Pid {
+    /// Runs one controller update and advances `plant` by `dt`.
+    pub fn step(&self, plant: &Plant, state: &mut PidState, goal: f32, dt: f32) {
+        let error = goal - state.position;
+        state.integral += error * dt;
+        let derivative = match state.previous_error {
+            Some(previous) if dt > 0.0 => (error - previous) / dt,
+            _ => 0.0,
+        };
+        state.previous_error = Some(error);
+
+        let output = self.proportional * error
+            + self.integral * state.integral
+            + self.derivative * derivative;
+        (state.position, state.velocity) = plant.step(state.position, state.velocity, output, dt);
+    }
+}
@@ -0,0 +1,19 @@
+//! The macroquad front end: pages, settings UI and drawing on top of the library.
+
+pub mod color;
+pub mod export;
+pub mod follow;
+pub mod frame_time;
+pub mod page;
+pub mod permalink;
+pub mod pid;
+pub mod preset;
+pub mod recording;
+pub mod rotation;
+pub mod signal;
+pub mod simulation;
+pub mod smoothing;
+pub mod spatial;
+pub mod spring;
+pub mod storage;
+pub mod validate;
@@ -0,0 +1,145 @@
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+use egui_macroquad::egui::{self, Slider};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use spring_it_on::color::ColorSpace;
+use spring_it_on::smoothing::SmootherState;
+
+use super::page::Page;
+use super::simulation::{ModelUi, MAX_HISTORY};
+use super::smoothing::Functions;
+use super::validate::{self, Validate};
+
+const STRIP_HEIGHT: f32 = 60.0;
+const INTERVAL: RangeInclusive<f32> = 0.1..=5.0;
+
+#[derive(Serialize, Deserialize)]
+struct ColorSettings {
+    functions: Functions,
+    from: [f32; 4],
+    to: [f32; 4],
+    /// Seconds between swapping the goal colour.
+    interval: f32,
+}
+
+impl Validate for ColorSettings {
+    fn validate(&mut self) -> Result<(), String> {
+        self.functions.validate()?;
+        validate::clamp_color("colour", &mut self.from)?;
+        validate::clamp_color("colour", &mut self.to)?;
+        validate::clamp("interval", &mut self.interval, INTERVAL)
+    }
+}
+
+struct Strip {
+    space: ColorSpace,
+    state: SmootherState<Vec4>,
+    /// sRGB, newest first.
+    history: VecDeque<Vec4>,
+}
+
+/// The same function fading between two colours in every [`ColorSpace`], one strip each.
+pub struct ColorPage {
+    settings: ColorSettings,
+    strips: Vec<Strip>,
+    time: f32,
+}
+
+impl ColorPage {
+    pub fn new() -> Self {
+        let from = [0.9, 0.1, 0.1, 1.0];
+        let strips = ColorSpace::ALL
+            .iter()
+            .map(|&space| Strip {
+                space,
+                state: SmootherState::at(space.from_srgb(Vec4::from(from))),
+                history: VecDeque::from([Vec4::from(from); MAX_HISTORY]),
+            })
+            .collect();
+        Self {
+            settings: ColorSettings {
+                functions: Functions::new(),
+                from,
+                to: [0.1, 0.8, 0.3, 1.0],
+                interval: 2.0,
+            },
+            strips,
+            time: 0.0,
+        }
+    }
+
+    fn goal(&self) -> Vec4 {
+        let swaps = (self.time / self.settings.interval.max(0.1)) as u32;
+        if swaps.is_multiple_of(2) {
+            Vec4::from(self.settings.to)
+        } else {
+            Vec4::from(self.settings.from)
+        }
+    }
+}
+
+impl Page for ColorPage {
+    fn name(&self) -> &str {
+        "Colors"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.settings.functions.ui(ui);
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Fade between");
+            ui.color_edit_button_rgba_unmultiplied(&mut self.settings.from);
+            ui.label("and");
+            ui.color_edit_button_rgba_unmultiplied(&mut self.settings.to);
+        });
+        ui.add(Slider::new(&mut self.settings.interval, INTERVAL).text("Swap every (s)"));
+        ui.label("Each strip runs the same function in another colour space, newest on the right");
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.time += dt;
+        let goal = self.goal();
+        let function = self.settings.functions.current_function();
+        for strip in &mut self.strips {
+            let color = function.execute_color(&mut strip.state, goal, dt, strip.space);
+            strip.history.push_front(color);
+            strip.history.truncate(MAX_HISTORY);
+        }
+    }
+
+    fn draw(&self) {
+        let left = 150.0;
+        let swatch = STRIP_HEIGHT;
+        let width = screen_width() - left - swatch - 40.0;
+        let bar = width / MAX_HISTORY as f32;
+        let top = (screen_height() - self.strips.len() as f32 * STRIP_HEIGHT * 1.5) / 2.0;
+        let goal = Color::from(self.goal().to_array());
+        for (row, strip) in self.strips.iter().enumerate() {
+            let y = top + row as f32 * STRIP_HEIGHT * 1.5;
+            draw_text(
+                strip.space.name(),
+                20.0,
+                y + STRIP_HEIGHT / 2.0,
+                24.0,
+                WHITE,
+            );
+            for (idx, color) in strip.history.iter().enumerate() {
+                let x = left + width - (idx + 1) as f32 * bar;
+                let color = Color::from(color.to_array());
+                draw_rectangle(x, y, bar + 0.5, STRIP_HEIGHT, color);
+            }
+            draw_rectangle(left + width + 20.0, y, swatch, STRIP_HEIGHT, goal);
+        }
+    }
+
+    fn save(&self) -> Option<String> {
+        ron::to_string(&self.settings).ok()
+    }
+
+    fn restore(&mut self, saved: &str) -> Result<(), String> {
+        self.settings = validate::from_ron(saved)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use egui_macroquad::egui;
+use spring_it_on::svg::Plot;
+
+/// Where the "Export SVG" button writes to, and how the last attempt went.
+pub struct SvgExport {
+    path: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for SvgExport {
+    fn default() -> Self {
+        Self {
+            path: "plot.svg".to_string(),
+            status: None,
+        }
+    }
+}
+
+impl SvgExport {
+    /// Only builds the plot when the button is clicked.
+    pub fn ui(&mut self, ui: &mut egui::Ui, plot: impl FnOnce() -> Plot) {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.path);
+            if ui.button("Export SVG").clicked() {
+                self.status = Some(
+                    std::fs::write(&self.path, plot().render())
+                        .map(|_| format!("Wrote {}", self.path))
+                        .map_err(|err| err.to_string()),
+                );
+            }
+        });
+        match &self.status {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            None => {}
+        }
+    }
+}
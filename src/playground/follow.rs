@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+
+use egui_macroquad::egui;
+use macroquad::prelude::*;
+use spring_it_on::smoothing::{Interpolate, SmootherState};
+
+use super::page::Page;
+use super::simulation::{ModelUi, MAX_HISTORY};
+use super::smoothing::Functions;
+use super::validate;
+
+/// A follower chasing the cursor in the plane, with its colour smoothed along the way.
+pub struct FollowPage {
+    functions: Functions,
+    position: SmootherState<Vec2>,
+    color: SmootherState<Color>,
+    trail: VecDeque<Vec2>,
+}
+
+impl FollowPage {
+    pub fn new() -> Self {
+        let center = vec2(screen_width(), screen_height()) / 2.0;
+        Self {
+            functions: Functions::new(),
+            position: SmootherState::at(center),
+            color: SmootherState::at(BLUE),
+            trail: VecDeque::new(),
+        }
+    }
+
+    /// Blue on the left edge of the screen, orange on the right.
+    fn target_color(target: Vec2) -> Color {
+        BLUE.lerp(ORANGE, (target.x / screen_width()).clamp(0.0, 1.0))
+    }
+}
+
+impl Page for FollowPage {
+    fn name(&self) -> &str {
+        "Follow cursor"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.functions.ui(ui);
+        ui.label("The follower chases the cursor and takes on the colour below it");
+    }
+
+    fn update(&mut self, dt: f32) {
+        let target = Vec2::from(mouse_position());
+        let function = self.functions.current_function();
+        let position = function.execute(&mut self.position, target, dt);
+        function.execute(&mut self.color, Self::target_color(target), dt);
+
+        self.trail.push_front(position);
+        self.trail.truncate(MAX_HISTORY);
+    }
+
+    fn draw(&self) {
+        let color = self.color.value;
+        let segments = self.trail.iter().zip(self.trail.iter().skip(1));
+        for (idx, (start, end)) in segments.enumerate() {
+            let fade = 1.0 - idx as f32 / MAX_HISTORY as f32;
+            let trail_color = Color { a: fade, ..color };
+            draw_line(start.x, start.y, end.x, end.y, 2.0, trail_color);
+        }
+        let target = Vec2::from(mouse_position());
+        draw_circle_lines(target.x, target.y, 14.0, 2.0, Self::target_color(target));
+        draw_circle(self.position.value.x, self.position.value.y, 12.0, color);
+    }
+
+    fn save(&self) -> Option<String> {
+        ron::to_string(&self.functions).ok()
+    }
+
+    fn restore(&mut self, saved: &str) -> Result<(), String> {
+        self.functions = validate::from_ron(saved)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use std::ops::RangeInclusive;
+
+use egui_macroquad::egui::{self, ComboBox, DragValue, Slider};
+use serde::{Deserialize, Serialize};
+use spring_it_on::frame_time::{parse_trace, FrameProfile, TimeUnit};
+
+use super::validate::{self, Validate};
+
+const JITTER: RangeInclusive<f32> = 0.0..=0.9;
+const SPIKE_PERIOD: RangeInclusive<u32> = 2..=60;
+const SPIKE_FACTOR: RangeInclusive<f32> = 1.0..=10.0;
+const HITCH_CHANCE: RangeInclusive<f32> = 0.0..=0.5;
+const HITCH_FACTOR: RangeInclusive<f32> = 1.0..=20.0;
+
+pub fn frame_profile_ui(profile: &mut FrameProfile, seed: &mut u64, ui: &mut egui::Ui) {
+    ComboBox::new("Frame times", "")
+        .selected_text(profile.name())
+        .show_ui(ui, |ui| {
+            for candidate in FrameProfile::ALL {
+                if ui
+                    .selectable_label(profile.name() == candidate.name(), candidate.name())
+                    .clicked()
+                {
+                    *profile = candidate;
+                }
+            }
+        });
+    match profile {
+        FrameProfile::Constant => {}
+        FrameProfile::Jitter { amount } => {
+            ui.add(Slider::new(amount, JITTER).text("Jitter"));
+        }
+        FrameProfile::Spikes { period, factor } => {
+            ui.add(Slider::new(period, SPIKE_PERIOD).text("Spike every"));
+            ui.add(Slider::new(factor, SPIKE_FACTOR).text("Spike factor"));
+        }
+        FrameProfile::Hitches { chance, factor } => {
+            ui.add(Slider::new(chance, HITCH_CHANCE).text("Hitch chance"));
+            ui.add(Slider::new(factor, HITCH_FACTOR).text("Hitch factor"));
+        }
+    }
+    if matches!(
+        profile,
+        FrameProfile::Jitter { .. } | FrameProfile::Hitches { .. }
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("Seed");
+            ui.add(DragValue::new(seed));
+        });
+    }
+}
+
+impl Validate for FrameProfile {
+    fn validate(&mut self) -> Result<(), String> {
+        match self {
+            FrameProfile::Constant => Ok(()),
+            FrameProfile::Jitter { amount } => validate::clamp("jitter", amount, JITTER),
+            FrameProfile::Spikes { period, factor } => {
+                validate::clamp("spike period", period, SPIKE_PERIOD)?;
+                validate::clamp("spike factor", factor, SPIKE_FACTOR)
+            }
+            FrameProfile::Hitches { chance, factor } => {
+                validate::clamp("hitch chance", chance, HITCH_CHANCE)?;
+                validate::clamp("hitch factor", factor, HITCH_FACTOR)
+            }
+        }
+    }
+}
+
+/// A recorded frame-time capture loaded from a file.
+#[derive(Clone, Deserialize)]
+#[serde(from = "SavedTrace")]
+pub struct FrameTrace {
+    path: String,
+    unit: TimeUnit,
+    frame_times: Option<Vec<f32>>,
+    error: Option<String>,
+}
+
+/// What of a [`FrameTrace`] is saved. The frame times are read from `path` again rather
+/// than stored, they would bloat permalinks and the autosave.
+#[derive(Serialize, Deserialize)]
+struct SavedTrace {
+    path: String,
+    unit: TimeUnit,
+    #[serde(default)]
+    loaded: bool,
+}
+
+impl From<SavedTrace> for FrameTrace {
+    fn from(saved: SavedTrace) -> Self {
+        let mut trace = Self {
+            path: saved.path,
+            unit: saved.unit,
+            frame_times: None,
+            error: None,
+        };
+        if saved.loaded {
+            trace.load();
+        }
+        trace
+    }
+}
+
+impl Serialize for FrameTrace {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedTrace {
+            path: self.path.clone(),
+            unit: self.unit,
+            loaded: self.frame_times.is_some(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Default for FrameTrace {
+    fn default() -> Self {
+        Self {
+            path: "frame_times.csv".to_string(),
+            unit: TimeUnit::Milliseconds,
+            frame_times: None,
+            error: None,
+        }
+    }
+}
+
+impl FrameTrace {
+    pub fn frame_times(&self) -> Option<&[f32]> {
+        self.frame_times.as_deref()
+    }
+
+    fn load(&mut self) {
+        let result = std::fs::read_to_string(&self.path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| parse_trace(&text, self.unit));
+        match result {
+            Ok(frame_times) => {
+                self.frame_times = Some(frame_times);
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Frame-time trace", |ui| {
+            ui.text_edit_singleline(&mut self.path);
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.unit, TimeUnit::Milliseconds, "ms");
+                ui.radio_value(&mut self.unit, TimeUnit::Seconds, "s");
+                if ui.button("Load").clicked() {
+                    self.load();
+                }
+                if self.frame_times.is_some() && ui.button("Clear").clicked() {
+                    self.frame_times = None;
+                }
+            });
+            if let Some(frame_times) = &self.frame_times {
+                let mean = frame_times.iter().sum::<f32>() / frame_times.len() as f32;
+                ui.label(format!(
+                    "{} frames, mean {:.2} ms",
+                    frame_times.len(),
+                    mean * 1000.0
+                ));
+            }
+            if let Some(error) = &self.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::VecDeque;
+
+use egui_macroquad::egui;
+use macroquad::prelude::*;
+
+use spring_it_on::recording::Frame;
+use spring_it_on::simulation::{Curve, REFERENCE_RATE};
+use spring_it_on::smoothing::lerp;
+use spring_it_on::svg::{Plot, Stroke};
+
+use super::export::SvgExport;
+use super::preset::{Preset, Presets};
+use super::recording::Recorder;
+use super::signal::GoalSource;
+use super::simulation::{
+    compare, draw_curve, draw_history, draw_legend, draw_reference, draw_rendered, draw_ticks,
+    Comparison, ModelUi, Simulation, MAX_HISTORY,
+};
+use super::validate;
+
+/// Live steps beyond this in a single frame are dropped rather than simulated.
+const MAX_TICKS_PER_FRAME: u32 = 32;
+
+/// One tab of the playground. Pages keep their state while another tab is shown.
+pub trait Page {
+    fn name(&self) -> &str;
+    /// Contents of the settings window.
+    fn ui(&mut self, ui: &mut egui::Ui);
+    fn update(&mut self, dt: f32);
+    fn draw(&self);
+    /// Settings to bring back on the next start, if the page has any.
+    fn save(&self) -> Option<String> {
+        None
+    }
+    /// Applies what [`Page::save`] returned, possibly in another session or edited by hand.
+    /// Leaves the page as it is when `saved` is invalid.
+    fn restore(&mut self, _saved: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// A page plotting a single value chasing a goal, either live or as a framerate comparison.
+pub struct SignalPage<M: ModelUi> {
+    name: &'static str,
+    model: M,
+    sim: Simulation<M>,
+    target_fps: f32,
+    goal: f32,
+    goal_source: GoalSource,
+    recorder: Recorder,
+    /// Real time the Live simulation has not caught up with yet.
+    unsimulated: f32,
+    /// Show the value interpolated between the last two ticks at every rendered frame.
+    interpolate: bool,
+    /// Interpolated values of recent frames, newest first, with their frame times.
+    rendered: VecDeque<(f32, f32)>,
+    state: M::State,
+    history: VecDeque<f32>,
+    comparison: Option<Comparison>,
+    /// Serialized settings and start height `comparison` was computed from, so it is only
+    /// computed again when they change.
+    compared: Option<(String, f32)>,
+    export: SvgExport,
+    presets: Presets<M>,
+}
+
+impl<M: ModelUi> SignalPage<M> {
+    pub fn new(name: &'static str, model: M) -> Self {
+        let center = screen_height() / 2.0;
+        Self {
+            name,
+            state: model.rest(center),
+            model,
+            sim: Simulation::Live,
+            target_fps: 60.0,
+            goal: center,
+            goal_source: GoalSource::default(),
+            recorder: Recorder::default(),
+            unsimulated: 0.0,
+            interpolate: false,
+            rendered: VecDeque::new(),
+            history: VecDeque::from([center; MAX_HISTORY]),
+            comparison: None,
+            compared: None,
+            export: SvgExport::default(),
+            presets: Presets::new(format!(
+                "{}.presets.ron",
+                name.to_lowercase().replace(' ', "_")
+            )),
+        }
+    }
+
+    /// Frame time and goal of a rendered frame that took `dt`, from the recorder while it
+    /// replays.
+    fn frame(&mut self, dt: f32) -> Frame {
+        let center = screen_height() / 2.0;
+        let mut goal = self.goal;
+        if let Some(next) = self.goal_source.next(center, dt) {
+            goal = next;
+        } else if is_mouse_button_down(MouseButton::Right) {
+            goal = mouse_position().1;
+        }
+        self.recorder.frame(Frame { dt, goal })
+    }
+
+    /// Advances the Live simulation by one step of `dt`.
+    fn tick(&mut self, dt: f32) {
+        let value = self.model.step(&mut self.state, self.goal, dt);
+        self.history.push_front(value);
+        self.history.resize(MAX_HISTORY, screen_height() / 2.0);
+    }
+
+    fn preset(&self) -> Preset<M> {
+        Preset {
+            model: self.model.clone(),
+            sim: self.sim.clone(),
+            target_fps: self.target_fps,
+            goal_source: self.goal_source.clone(),
+            interpolate: self.interpolate,
+        }
+    }
+
+    fn apply(&mut self, preset: Preset<M>) {
+        self.model = preset.model;
+        self.sim = preset.sim;
+        self.target_fps = preset.target_fps;
+        self.goal_source = preset.goal_source;
+        self.goal_source.restart();
+        self.interpolate = preset.interpolate;
+        self.comparison = None;
+    }
+
+    /// The curves currently on screen, for exporting.
+    fn plot(&self) -> Plot {
+        let mut plot = Plot::new(800.0, 500.0);
+        match self.sim {
+            Simulation::Live => {
+                let target_dt = 1.0 / self.target_fps;
+                let (times, values) = self
+                    .history
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(idx, &value)| (-(idx as f32) * target_dt, value))
+                    .unzip();
+                let history = Curve { times, values };
+                let goal = Curve {
+                    times: vec![history.times[0], 0.0],
+                    values: vec![self.goal, self.goal],
+                };
+                plot = plot.x_label("time before now (s)");
+                plot.curve(self.model.label(), BLUE.into(), Stroke::FRAMES, &history);
+                plot.curve("goal", MAROON.into(), Stroke::REFERENCE, &goal);
+                if !self.rendered.is_empty() {
+                    // Newest first, each frame is its frame time before the one after it.
+                    let mut age = 0.0;
+                    let (times, values) = self
+                        .rendered
+                        .iter()
+                        .map(|&(dt, value)| {
+                            let time = -age;
+                            age += dt;
+                            (time, value)
+                        })
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .rev()
+                        .unzip();
+                    let rendered = Curve { times, values };
+                    plot.curve("rendered", ORANGE.into(), Stroke::FRAMES, &rendered);
+                }
+            }
+            Simulation::Compare { ref settings } => {
+                for result in self.comparison.iter().flat_map(|c| &c.series) {
+                    if settings.show_reference {
+                        let label = format!("{} reference", result.label);
+                        let color = result.color.into();
+                        plot.curve(label, color, Stroke::REFERENCE, &result.reference);
+                    }
+                    let color = result.color.into();
+                    if let Some(ticks) = &result.ticks {
+                        let label = format!("{} raw ticks", result.label);
+                        plot.curve(label, color, Stroke::REFERENCE, ticks);
+                    }
+                    plot.curve(&result.label, color, Stroke::FRAMES, &result.curve);
+                }
+            }
+        }
+        plot
+    }
+}
+
+impl<M: ModelUi> Page for SignalPage<M> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let mut presets = std::mem::take(&mut self.presets);
+        if let Some(preset) = presets.ui(ui, || self.preset()) {
+            self.apply(preset);
+        }
+        self.presets = presets;
+        ui.separator();
+        self.sim.mode_ui(ui, &self.model);
+        if self.sim == Simulation::Live {
+            self.model.ui(ui);
+            ui.separator();
+            self.goal_source.ui(ui);
+            if let Some(start) = self.recorder.ui(ui, self.history[0]) {
+                self.state = self.model.rest(start);
+                // Ticks fall on the same frames when replayed.
+                self.unsimulated = 0.0;
+            }
+        }
+        self.sim.settings_ui(ui, &self.model, &mut self.target_fps);
+        if self.sim == Simulation::Live {
+            ui.checkbox(&mut self.interpolate, "Interpolate between ticks")
+                .on_hover_text("Target fps becomes the tick rate of a fixed update, orange shows what would be rendered every frame");
+        }
+        if let Simulation::Compare { ref settings } = self.sim {
+            if settings.show_reference {
+                ui.label(format!(
+                    "References without a closed form are stepped at {REFERENCE_RATE} Hz"
+                ));
+            }
+            if let Some(comparison) = &self.comparison {
+                comparison.ui(ui);
+            }
+        }
+        ui.separator();
+        let mut export = std::mem::take(&mut self.export);
+        export.ui(ui, || self.plot());
+        self.export = export;
+    }
+
+    fn update(&mut self, dt: f32) {
+        match self.sim {
+            Simulation::Live => {
+                let frame = self.frame(dt);
+                let dt = frame.dt;
+                self.goal = frame.goal;
+                // Tick at the target rate no matter how fast frames are rendered.
+                self.unsimulated += dt;
+                let tick = 1.0 / self.target_fps;
+                let mut ticks = 0;
+                while self.unsimulated >= tick {
+                    self.unsimulated -= tick;
+                    ticks += 1;
+                    if ticks > MAX_TICKS_PER_FRAME {
+                        // After a long hitch, drop the backlog instead of catching up on it.
+                        self.unsimulated = 0.0;
+                        break;
+                    }
+                    self.tick(tick);
+                }
+                if self.interpolate {
+                    let alpha = self.unsimulated / tick;
+                    let value = lerp(self.history[1], self.history[0], alpha);
+                    self.rendered.push_front((dt, value));
+                    // Drop frames that scrolled off the left edge.
+                    let mut age = 0.0;
+                    let visible = self.rendered.iter().take_while(|&&(dt, _)| {
+                        age += dt;
+                        age < 1.0
+                    });
+                    let len = visible.count() + 1;
+                    self.rendered.truncate(len);
+                } else {
+                    self.rendered.clear();
+                }
+            }
+            Simulation::Compare { ref settings } => {
+                let start = screen_height();
+                let goal = 0.0;
+
+                let key = ron::to_string(settings).ok().map(|saved| (saved, start));
+                if self.comparison.is_none() || key.is_none() || key != self.compared {
+                    self.comparison = Some(compare(settings, start, goal));
+                    self.compared = key;
+                }
+            }
+        }
+    }
+
+    fn draw(&self) {
+        match self.sim {
+            Simulation::Live => {
+                draw_history(&self.history, self.goal, 1.0 / self.target_fps);
+                draw_rendered(&self.rendered);
+            }
+            Simulation::Compare { ref settings } => {
+                if let Some(comparison) = &self.comparison {
+                    for result in &comparison.series {
+                        if settings.show_reference {
+                            draw_reference(
+                                &result.reference,
+                                settings.simulating_time,
+                                result.color,
+                            );
+                        }
+                        if let Some(ticks) = &result.ticks {
+                            draw_ticks(ticks, settings.simulating_time, result.color);
+                        }
+                        draw_curve(&result.curve, settings.simulating_time, result.color);
+                    }
+                    draw_legend(comparison);
+                }
+            }
+        }
+    }
+
+    fn save(&self) -> Option<String> {
+        ron::to_string(&self.preset()).ok()
+    }
+
+    fn restore(&mut self, saved: &str) -> Result<(), String> {
+        self.apply(validate::from_ron(saved)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+//! Where the playground reads and publishes its permalink: the URL fragment in the browser,
+//! the first command line argument on native.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn current() -> Option<String> {
+    std::env::args().nth(1)
+}
+
+/// Native builds have no address bar, [`link`] gives a command line instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn publish(_fragment: &str) {}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub const SHARE_HINT: &str = "Copies a command line starting the playground with these settings";
+
+/// Command line opening `fragment`, quoted for a POSIX shell.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn link(fragment: &str) -> String {
+    let program = std::env::args().next();
+    let program = program.as_deref().unwrap_or("spring-it-on");
+    // The fragment is percent-encoded, only the program may need escaping.
+    format!("'{}' '#{fragment}'", program.replace('\'', r"'\''"))
+}
+
+/// Implemented by the plugin registered in `index.html`.
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    fn playground_fragment_len() -> i32;
+    fn playground_fragment_read(out: *mut u8, out_len: usize);
+    fn playground_fragment_write(fragment: *const u8, len: usize);
+    fn playground_location_href_len() -> i32;
+    fn playground_location_href_read(out: *mut u8, out_len: usize);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn current() -> Option<String> {
+    let len = usize::try_from(unsafe { playground_fragment_len() }).ok()?;
+    let mut bytes = vec![0; len];
+    unsafe { playground_fragment_read(bytes.as_mut_ptr(), len) };
+    String::from_utf8(bytes)
+        .ok()
+        .filter(|fragment| !fragment.is_empty())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn publish(fragment: &str) {
+    unsafe { playground_fragment_write(fragment.as_ptr(), fragment.len()) };
+}
+
+#[cfg(target_arch = "wasm32")]
+pub const SHARE_HINT: &str = "Puts a link to these settings in the address bar and the clipboard";
+
+/// The page address with `fragment` in place of the current one.
+#[cfg(target_arch = "wasm32")]
+pub fn link(fragment: &str) -> String {
+    let len = usize::try_from(unsafe { playground_location_href_len() }).unwrap_or(0);
+    let mut bytes = vec![0; len];
+    unsafe { playground_location_href_read(bytes.as_mut_ptr(), len) };
+    let href = String::from_utf8(bytes).unwrap_or_default();
+    let address = href.split('#').next().unwrap_or_default();
+    format!("{address}#{fragment}")
+}
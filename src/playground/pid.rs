@@ -0,0 +1,153 @@
+use std::ops::RangeInclusive;
+
+use egui_macroquad::egui::{self, ComboBox, Slider};
+use serde::{Deserialize, Serialize};
+use spring_it_on::pid::{Pid, PidState, Plant};
+use spring_it_on::simulation::Model;
+
+use super::simulation::ModelUi;
+use super::validate::{self, Validate};
+
+const TIME_CONSTANT: RangeInclusive<f32> = 0.01..=2.0;
+const MASS: RangeInclusive<f32> = 0.1..=10.0;
+const FRICTION: RangeInclusive<f32> = 0.0..=10.0;
+const GRAVITY: RangeInclusive<f32> = 0.0..=2000.0;
+const PROPORTIONAL: RangeInclusive<f32> = 0.0..=100.0;
+const INTEGRAL: RangeInclusive<f32> = 0.0..=50.0;
+const DERIVATIVE: RangeInclusive<f32> = 0.0..=20.0;
+
+fn plant_ui(plant: &mut Plant, ui: &mut egui::Ui) {
+    match plant {
+        Plant::FirstOrderLag { time_constant } => {
+            ui.add(Slider::new(time_constant, TIME_CONSTANT).text("Time constant"));
+        }
+        Plant::PointMass { mass, friction } => {
+            ui.add(Slider::new(mass, MASS).text("Mass"));
+            ui.add(Slider::new(friction, FRICTION).text("Friction"));
+        }
+        Plant::MassUnderGravity { mass, gravity } => {
+            ui.add(Slider::new(mass, MASS).text("Mass"));
+            ui.add(Slider::new(gravity, GRAVITY).text("Gravity"));
+        }
+    }
+}
+
+fn pid_ui(pid: &mut Pid, ui: &mut egui::Ui) {
+    ui.add(Slider::new(&mut pid.proportional, PROPORTIONAL).text("P"));
+    ui.add(Slider::new(&mut pid.integral, INTEGRAL).text("I"));
+    ui.add(Slider::new(&mut pid.derivative, DERIVATIVE).text("D"));
+}
+
+fn validate_plant(plant: &mut Plant) -> Result<(), String> {
+    match plant {
+        Plant::FirstOrderLag { time_constant } => {
+            validate::clamp("time constant", time_constant, TIME_CONSTANT)
+        }
+        Plant::PointMass { mass, friction } => {
+            validate::clamp("mass", mass, MASS)?;
+            validate::clamp("friction", friction, FRICTION)
+        }
+        Plant::MassUnderGravity { mass, gravity } => {
+            validate::clamp("mass", mass, MASS)?;
+            validate::clamp("gravity", gravity, GRAVITY)
+        }
+    }
+}
+
+fn validate_pid(pid: &mut Pid) -> Result<(), String> {
+    validate::clamp("P", &mut pid.proportional, PROPORTIONAL)?;
+    validate::clamp("I", &mut pid.integral, INTEGRAL)?;
+    validate::clamp("D", &mut pid.derivative, DERIVATIVE)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Plants {
+    plants: Vec<Plant>,
+    selected_index: usize,
+}
+
+impl Default for Plants {
+    fn default() -> Self {
+        Self {
+            plants: vec![
+                Plant::FirstOrderLag { time_constant: 0.5 },
+                Plant::PointMass {
+                    mass: 1.0,
+                    friction: 0.0,
+                },
+                Plant::MassUnderGravity {
+                    mass: 1.0,
+                    gravity: 500.0,
+                },
+            ],
+            selected_index: 1,
+        }
+    }
+}
+
+impl Plants {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ComboBox::new("Plants", "").show_index(
+            ui,
+            &mut self.selected_index,
+            self.plants.len(),
+            |idx| self.plants[idx].name().to_string(),
+        );
+        plant_ui(self.current_plant_mut(), ui);
+    }
+
+    pub fn current_plant(&self) -> &Plant {
+        &self.plants[self.selected_index]
+    }
+
+    fn current_plant_mut(&mut self) -> &mut Plant {
+        &mut self.plants[self.selected_index]
+    }
+}
+
+/// A PID controller together with the plants it can drive.
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct Controller {
+    pub pid: Pid,
+    pub plants: Plants,
+}
+
+impl Model for Controller {
+    type State = PidState;
+
+    fn label(&self) -> String {
+        let pid = &self.pid;
+        format!(
+            "{} P {:.1} I {:.1} D {:.1}",
+            self.plants.current_plant().name(),
+            pid.proportional,
+            pid.integral,
+            pid.derivative
+        )
+    }
+
+    fn rest(&self, value: f32) -> PidState {
+        PidState::at(value)
+    }
+
+    fn step(&self, state: &mut PidState, goal: f32, dt: f32) -> f32 {
+        self.pid.step(self.plants.current_plant(), state, goal, dt);
+        state.position
+    }
+}
+
+impl Validate for Controller {
+    fn validate(&mut self) -> Result<(), String> {
+        let plants = &mut self.plants;
+        validate::index("plant", plants.selected_index, plants.plants.len())?;
+        plants.plants.iter_mut().try_for_each(validate_plant)?;
+        validate_pid(&mut self.pid)
+    }
+}
+
+impl ModelUi for Controller {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.plants.ui(ui);
+        pid_ui(&mut self.pid, ui);
+    }
+}
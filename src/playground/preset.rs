@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use egui_macroquad::egui;
+use serde::{Deserialize, Serialize};
+
+use super::signal::GoalSource;
+use super::simulation::{ModelUi, Simulation, FRAME_RATES};
+use super::validate::{self, Validate};
+
+/// Everything needed to bring a page back to a tuned configuration.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "M: ModelUi")]
+pub struct Preset<M> {
+    pub model: M,
+    pub sim: Simulation<M>,
+    pub target_fps: f32,
+    #[serde(default)]
+    pub goal_source: GoalSource,
+    #[serde(default)]
+    pub interpolate: bool,
+}
+
+impl<M: ModelUi> Validate for Preset<M> {
+    fn validate(&mut self) -> Result<(), String> {
+        self.model.validate()?;
+        self.sim.validate()?;
+        validate::clamp("target fps", &mut self.target_fps, FRAME_RATES)?;
+        self.goal_source.validate()
+    }
+}
+
+/// Named presets of one page, kept in a RON file so they can be shared.
+pub struct Presets<M> {
+    path: String,
+    presets: BTreeMap<String, Preset<M>>,
+    name: String,
+    error: Option<String>,
+}
+
+impl<M> Default for Presets<M> {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            presets: BTreeMap::new(),
+            name: "My preset".to_string(),
+            error: None,
+        }
+    }
+}
+
+impl<M: ModelUi> Presets<M> {
+    /// Reads `path` if it exists.
+    pub fn new(path: String) -> Self {
+        let mut presets = Self {
+            path,
+            ..Default::default()
+        };
+        if std::path::Path::new(&presets.path).exists() {
+            presets.load();
+        }
+        presets
+    }
+
+    /// Replaces the presets with those in the file, leaving out any that don't validate.
+    fn load(&mut self) {
+        let result = std::fs::read_to_string(&self.path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| ron::from_str(&text).map_err(|err| err.to_string()));
+        match result {
+            Ok(mut presets) => {
+                let mut invalid = Vec::new();
+                BTreeMap::retain(&mut presets, |name, preset: &mut Preset<M>| {
+                    match preset.validate() {
+                        Ok(()) => true,
+                        Err(err) => {
+                            invalid.push(format!("{name}: {err}"));
+                            false
+                        }
+                    }
+                });
+                self.presets = presets;
+                self.error = (!invalid.is_empty())
+                    .then(|| format!("Skipped invalid presets\n{}", invalid.join("\n")));
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn save(&mut self) {
+        let result = ron::ser::to_string_pretty(&self.presets, Default::default())
+            .map_err(|err| err.to_string())
+            .and_then(|text| std::fs::write(&self.path, text).map_err(|err| err.to_string()));
+        self.error = result.err();
+    }
+
+    /// Returns the preset to apply when one was picked. `current` is only called when saving.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        current: impl FnOnce() -> Preset<M>,
+    ) -> Option<Preset<M>> {
+        let mut picked = None;
+        ui.collapsing("Presets", |ui| {
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.path);
+                if ui.button("Reload").clicked() {
+                    self.load();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.name);
+                if ui.button("Save").clicked() && !self.name.is_empty() {
+                    self.presets.insert(self.name.clone(), current());
+                    self.save();
+                }
+            });
+            let mut removed = None;
+            for (name, preset) in &self.presets {
+                ui.horizontal(|ui| {
+                    if ui.button("Load").clicked() {
+                        picked = Some(preset.clone());
+                    }
+                    if ui.button("Delete").clicked() {
+                        removed = Some(name.clone());
+                    }
+                    ui.label(name);
+                });
+            }
+            if let Some(name) = removed {
+                self.presets.remove(&name);
+                self.save();
+            }
+            if let Some(error) = &self.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+        picked
+    }
+}
@@ -0,0 +1,129 @@
+use egui_macroquad::egui;
+use spring_it_on::recording::{Frame, Recording};
+
+enum Mode {
+    Idle,
+    Recording(Recording),
+    /// Index of the next frame to play back.
+    Replaying(Recording, usize),
+}
+
+/// Records the frame times and goal of a Live page to a file, and feeds them back later.
+pub struct Recorder {
+    path: String,
+    mode: Mode,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self {
+            path: "session.csv".to_string(),
+            mode: Mode::Idle,
+            status: None,
+        }
+    }
+}
+
+impl Recorder {
+    /// Passes `frame` through while idle or recording, and returns the recorded frame
+    /// instead while replaying.
+    pub fn frame(&mut self, frame: Frame) -> Frame {
+        match &mut self.mode {
+            Mode::Idle => frame,
+            Mode::Recording(recording) => {
+                recording.frames.push(frame);
+                frame
+            }
+            Mode::Replaying(recording, next) => {
+                let recorded = recording.frames[*next];
+                *next += 1;
+                if *next == recording.frames.len() {
+                    self.status = Some(Ok("Replay finished".to_string()));
+                    self.mode = Mode::Idle;
+                }
+                recorded
+            }
+        }
+    }
+
+    fn stop_recording(&mut self) {
+        let Mode::Recording(recording) = std::mem::replace(&mut self.mode, Mode::Idle) else {
+            return;
+        };
+        self.status = Some(
+            std::fs::write(&self.path, recording.to_csv())
+                .map(|_| {
+                    format!(
+                        "Wrote {} frames ({:.1} s) to {}",
+                        recording.frames.len(),
+                        recording.duration(),
+                        self.path
+                    )
+                })
+                .map_err(|err| err.to_string()),
+        );
+    }
+
+    fn start_replay(&mut self) -> Option<f32> {
+        let result = std::fs::read_to_string(&self.path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| Recording::parse(&text));
+        match result {
+            Ok(recording) => {
+                let start = recording.start;
+                self.status = None;
+                self.mode = Mode::Replaying(recording, 0);
+                Some(start)
+            }
+            Err(err) => {
+                self.status = Some(Err(err));
+                None
+            }
+        }
+    }
+
+    /// `value` is where the model is now, recordings start from there. Returns the value the
+    /// model has to be put to rest at when a recording or replay starts, so replays begin
+    /// from the same state as the recording did.
+    pub fn ui(&mut self, ui: &mut egui::Ui, value: f32) -> Option<f32> {
+        let mut start = None;
+        ui.collapsing("Record and replay", |ui| {
+            ui.text_edit_singleline(&mut self.path);
+            ui.horizontal(|ui| match &self.mode {
+                Mode::Idle => {
+                    if ui.button("Record").clicked() {
+                        self.mode = Mode::Recording(Recording::new(value));
+                        self.status = None;
+                        start = Some(value);
+                    }
+                    if ui.button("Replay").clicked() {
+                        start = self.start_replay();
+                    }
+                }
+                Mode::Recording(recording) => {
+                    ui.label(format!("Recording, {} frames", recording.frames.len()));
+                    if ui.button("Stop").clicked() {
+                        self.stop_recording();
+                    }
+                }
+                Mode::Replaying(recording, next) => {
+                    ui.label(format!("Replaying {next}/{}", recording.frames.len()));
+                    if ui.button("Stop").clicked() {
+                        self.mode = Mode::Idle;
+                    }
+                }
+            });
+            match &self.status {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+                None => {}
+            }
+        });
+        start
+    }
+}
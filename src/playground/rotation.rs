@@ -0,0 +1,149 @@
+use egui_macroquad::egui;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use spring_it_on::smoothing::{AngleUnit, RotationBlend, SmootherState};
+
+use super::page::Page;
+use super::simulation::ModelUi;
+use super::smoothing::Functions;
+use super::validate::{self, Validate};
+
+const ARROW_LENGTH: f32 = 200.0;
+
+#[derive(Serialize, Deserialize)]
+struct RotationSettings {
+    functions: Functions,
+    unit: AngleUnit,
+    blend: RotationBlend,
+    show_naive: bool,
+}
+
+impl Validate for RotationSettings {
+    fn validate(&mut self) -> Result<(), String> {
+        self.functions.validate()
+    }
+}
+
+/// Arrows in the middle of the screen turning to point at the cursor.
+pub struct RotationPage {
+    settings: RotationSettings,
+    /// Smoothed as a plain number, so it unwinds the long way round past half a turn.
+    naive: SmootherState,
+    /// In `settings.unit`.
+    angle: SmootherState,
+    rotation: SmootherState<Quat>,
+}
+
+impl RotationPage {
+    pub fn new() -> Self {
+        Self {
+            settings: RotationSettings {
+                functions: Functions::new(),
+                unit: AngleUnit::Radians,
+                blend: RotationBlend::Slerp,
+                show_naive: true,
+            },
+            naive: SmootherState::at(0.0),
+            angle: SmootherState::at(0.0),
+            rotation: SmootherState::at(Quat::IDENTITY),
+        }
+    }
+
+    fn center() -> Vec2 {
+        vec2(screen_width(), screen_height()) / 2.0
+    }
+
+    /// Direction of the cursor seen from the middle of the screen, in radians.
+    fn target_angle() -> f32 {
+        let to_mouse = Vec2::from(mouse_position()) - Self::center();
+        to_mouse.y.atan2(to_mouse.x)
+    }
+
+    fn set_unit(&mut self, unit: AngleUnit) {
+        let scale = unit.full_turn() / self.settings.unit.full_turn();
+        self.angle.value *= scale;
+        self.angle.velocity *= scale;
+        self.settings.unit = unit;
+    }
+}
+
+fn draw_arrow(center: Vec2, direction: Vec2, length: f32, color: Color) {
+    let tip = center + direction * length;
+    let side = direction.perp() * 10.0;
+    let base = tip - direction * 20.0;
+    draw_line(center.x, center.y, tip.x, tip.y, 4.0, color);
+    draw_triangle(tip, base + side, base - side, color);
+}
+
+impl Page for RotationPage {
+    fn name(&self) -> &str {
+        "Rotation"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.settings.functions.ui(ui);
+        ui.separator();
+        let mut unit = self.settings.unit;
+        ui.horizontal(|ui| {
+            ui.label("Angle in");
+            ui.radio_value(&mut unit, AngleUnit::Radians, "radians");
+            ui.radio_value(&mut unit, AngleUnit::Degrees, "degrees");
+        });
+        if unit != self.settings.unit {
+            self.set_unit(unit);
+        }
+        ui.horizontal(|ui| {
+            ui.label("Quaternion");
+            ui.radio_value(&mut self.settings.blend, RotationBlend::Slerp, "slerp");
+            ui.radio_value(&mut self.settings.blend, RotationBlend::Nlerp, "nlerp");
+        });
+        ui.checkbox(&mut self.settings.show_naive, "Show naive angle");
+        ui.label(
+            "Blue smooths the shortest arc to the cursor, orange a quaternion, gray the raw angle",
+        );
+    }
+
+    fn update(&mut self, dt: f32) {
+        let target = Self::target_angle();
+        let unit = self.settings.unit;
+        let function = self.settings.functions.current_function();
+        function.execute(&mut self.naive, target, dt);
+        let to = target / AngleUnit::Radians.full_turn() * unit.full_turn();
+        function.execute_angle(&mut self.angle, to, dt, unit);
+        let rotation = Quat::from_rotation_z(target);
+        function.execute_rotation(&mut self.rotation, rotation, dt, self.settings.blend);
+    }
+
+    fn draw(&self) {
+        let center = Self::center();
+        draw_circle_lines(center.x, center.y, ARROW_LENGTH, 1.0, GRAY);
+        if self.settings.show_naive {
+            draw_arrow(
+                center,
+                Vec2::from_angle(self.naive.value),
+                ARROW_LENGTH,
+                GRAY,
+            );
+        }
+        let radians =
+            self.angle.value / self.settings.unit.full_turn() * AngleUnit::Radians.full_turn();
+        draw_arrow(center, Vec2::from_angle(radians), ARROW_LENGTH, BLUE);
+        let direction = (self.rotation.value * Vec3::X).truncate();
+        draw_arrow(center, direction, ARROW_LENGTH * 0.8, ORANGE);
+        let target = Vec2::from_angle(Self::target_angle()) * ARROW_LENGTH + center;
+        draw_circle_lines(target.x, target.y, 14.0, 2.0, MAROON);
+    }
+
+    fn save(&self) -> Option<String> {
+        ron::to_string(&self.settings).ok()
+    }
+
+    fn restore(&mut self, saved: &str) -> Result<(), String> {
+        let settings: RotationSettings = validate::from_ron(saved)?;
+        self.settings.functions = settings.functions;
+        self.set_unit(settings.unit);
+        self.settings.blend = settings.blend;
+        self.settings.show_naive = settings.show_naive;
+        Ok(())
+    }
+}
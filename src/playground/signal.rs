@@ -0,0 +1,131 @@
+use std::ops::RangeInclusive;
+
+use egui_macroquad::egui::{self, ComboBox, DragValue, Slider};
+use serde::{Deserialize, Serialize};
+use spring_it_on::signal::{GoalSignal, SignalGenerator};
+
+use super::validate::{self, Validate};
+
+const INTERVAL: RangeInclusive<f32> = 0.1..=5.0;
+const FREQUENCY: RangeInclusive<f32> = 0.05..=5.0;
+const SPEED: RangeInclusive<f32> = 0.1..=5.0;
+const AMPLITUDE: RangeInclusive<f32> = 0.0..=400.0;
+
+/// Drives the goal of a Live page, either from the mouse or from a [`GoalSignal`].
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "SavedGoalSource")]
+pub struct GoalSource {
+    signal: GoalSignal,
+    /// How far the goal swings away from the center, in pixels.
+    amplitude: f32,
+    seed: u64,
+    #[serde(skip_serializing)]
+    generator: SignalGenerator,
+}
+
+/// What of a [`GoalSource`] is saved. The generator is started over from `seed`.
+#[derive(Deserialize)]
+struct SavedGoalSource {
+    signal: GoalSignal,
+    amplitude: f32,
+    seed: u64,
+}
+
+impl From<SavedGoalSource> for GoalSource {
+    fn from(saved: SavedGoalSource) -> Self {
+        Self {
+            signal: saved.signal,
+            amplitude: saved.amplitude,
+            seed: saved.seed,
+            generator: SignalGenerator::new(saved.seed),
+        }
+    }
+}
+
+impl Default for GoalSource {
+    fn default() -> Self {
+        Self {
+            signal: GoalSignal::Manual,
+            amplitude: 200.0,
+            seed: 0,
+            generator: SignalGenerator::new(0),
+        }
+    }
+}
+
+impl GoalSource {
+    /// Goal for the next frame, or `None` when it is set by hand.
+    pub fn next(&mut self, center: f32, dt: f32) -> Option<f32> {
+        let value = self.generator.next(&self.signal, dt)?;
+        Some(center + self.amplitude * value)
+    }
+
+    /// Starts the signal over.
+    pub fn restart(&mut self) {
+        self.generator = SignalGenerator::new(self.seed);
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let before = (self.signal.name().to_string(), self.seed);
+        ComboBox::new("Goal", "")
+            .selected_text(self.signal.name())
+            .show_ui(ui, |ui| {
+                for candidate in GoalSignal::ALL {
+                    if ui
+                        .selectable_label(self.signal.name() == candidate.name(), candidate.name())
+                        .clicked()
+                    {
+                        self.signal = candidate;
+                    }
+                }
+            });
+        match &mut self.signal {
+            GoalSignal::Manual => {
+                ui.label("Hold the right mouse button to move the goal");
+                return;
+            }
+            GoalSignal::Step { interval } => {
+                ui.add(Slider::new(interval, INTERVAL).text("Interval"));
+            }
+            GoalSignal::Square { frequency }
+            | GoalSignal::Sine { frequency }
+            | GoalSignal::Ramp { frequency }
+            | GoalSignal::Noise { frequency } => {
+                ui.add(Slider::new(frequency, FREQUENCY).text("Frequency"));
+            }
+            GoalSignal::RandomWalk { speed } => {
+                ui.add(Slider::new(speed, SPEED).text("Speed"));
+            }
+        }
+        ui.add(Slider::new(&mut self.amplitude, AMPLITUDE).text("Amplitude"));
+        if matches!(
+            self.signal,
+            GoalSignal::Step { .. } | GoalSignal::RandomWalk { .. } | GoalSignal::Noise { .. }
+        ) {
+            ui.horizontal(|ui| {
+                ui.label("Seed");
+                ui.add(DragValue::new(&mut self.seed));
+            });
+        }
+        if ui.button("Restart").clicked() || before != (self.signal.name().to_string(), self.seed) {
+            self.restart();
+        }
+    }
+}
+
+impl Validate for GoalSource {
+    fn validate(&mut self) -> Result<(), String> {
+        match &mut self.signal {
+            GoalSignal::Manual => {}
+            GoalSignal::Step { interval } => validate::clamp("interval", interval, INTERVAL)?,
+            GoalSignal::Square { frequency }
+            | GoalSignal::Sine { frequency }
+            | GoalSignal::Ramp { frequency }
+            | GoalSignal::Noise { frequency } => {
+                validate::clamp("frequency", frequency, FREQUENCY)?
+            }
+            GoalSignal::RandomWalk { speed } => validate::clamp("speed", speed, SPEED)?,
+        }
+        validate::clamp("amplitude", &mut self.amplitude, AMPLITUDE)
+    }
+}
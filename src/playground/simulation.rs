@@ -0,0 +1,532 @@
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+use egui_macroquad::egui::{self, ComboBox, Slider};
+use macroquad::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use spring_it_on::frame_time::FrameProfile;
+use spring_it_on::simulation::{
+    reference, simulate, simulate_fixed, Curve, Deviation, Model, SubStepped,
+};
+
+use super::frame_time::{frame_profile_ui, FrameTrace};
+use super::validate::{self, Validate};
+
+pub const MAX_HISTORY: usize = 240;
+/// Range of every framerate slider.
+pub const FRAME_RATES: RangeInclusive<f32> = 10.0..=240.0;
+const TICK_RATES: RangeInclusive<f32> = 5.0..=240.0;
+const SIMULATING_TIMES: RangeInclusive<f32> = 0.1..=10.0;
+/// Series beyond this can't be added, each one is simulated every frame.
+const MAX_SERIES: usize = 16;
+/// Largest sub-step count tried when looking for one that matches the first series.
+const MAX_SUB_STEPS: u32 = 64;
+/// Max deviation from the first series at which a series counts as matching it.
+const MATCH_TOLERANCE: f32 = 0.01;
+
+/// A model the playground can tune in the settings window and save in presets.
+pub trait ModelUi: Model + Clone + Serialize + DeserializeOwned + Validate {
+    fn ui(&mut self, ui: &mut egui::Ui);
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Simulation<M> {
+    Live,
+    Compare { settings: CompareSettings<M> },
+}
+impl<M> Simulation<M> {
+    fn name(&self) -> &str {
+        match self {
+            Simulation::Live => "Live",
+            Simulation::Compare { .. } => "Compare",
+        }
+    }
+}
+impl<M: ModelUi> Simulation<M> {
+    /// Switching to Compare starts out with copies of `model`.
+    pub fn mode_ui(&mut self, ui: &mut egui::Ui, model: &M) {
+        ComboBox::new("Box", "")
+            .selected_text(self.name())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(self, Simulation::Live, "Live");
+
+                if ui.selectable_label(false, "Compare").clicked() {
+                    *self = Simulation::Compare {
+                        settings: CompareSettings::new(model),
+                    };
+                }
+            });
+    }
+
+    /// Series added to an empty Compare start out with a copy of `model`.
+    pub fn settings_ui(&mut self, ui: &mut egui::Ui, model: &M, target_fps: &mut f32) {
+        match self {
+            Simulation::Compare { settings } => settings.ui(ui, model),
+            Simulation::Live => {
+                ui.add(Slider::new(target_fps, FRAME_RATES).text("Target fps"));
+            }
+        }
+    }
+}
+impl<M: ModelUi> Validate for Simulation<M> {
+    fn validate(&mut self) -> Result<(), String> {
+        match self {
+            Simulation::Live => Ok(()),
+            Simulation::Compare { settings } => settings.validate(),
+        }
+    }
+}
+impl<M> PartialEq for Simulation<M> {
+    fn eq(&self, other: &Self) -> bool {
+        std::mem::discriminant(self).eq(&std::mem::discriminant(other))
+    }
+}
+
+const PALETTE: [Color; 6] = [BLUE, ORANGE, GREEN, PINK, YELLOW, VIOLET];
+
+/// macroquad's `Color` isn't serializable, so series store it as `[r, g, b, a]`.
+mod rgba {
+    use macroquad::prelude::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        let rgba: [f32; 4] = (*color).into();
+        rgba.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        <[f32; 4]>::deserialize(deserializer).map(Color::from)
+    }
+}
+
+fn default_sub_steps() -> u32 {
+    1
+}
+
+/// Output of [`Series::simulate`].
+pub struct SeriesRun {
+    /// The value at every frame.
+    pub curve: Curve,
+    /// With a fixed timestep, the latest raw tick at every frame.
+    pub ticks: Option<Curve>,
+    /// How often the model was stepped.
+    pub evaluations: usize,
+}
+
+/// One curve of a comparison: a model stepped with its own frame times.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Series<M> {
+    pub model: M,
+    pub frame_rate: f32,
+    pub profile: FrameProfile,
+    pub seed: u64,
+    /// Replaces `frame_rate` and `profile` when loaded.
+    pub trace: FrameTrace,
+    /// When set, the model ticks at this fixed rate and frames show the interpolated value.
+    #[serde(default)]
+    pub tick_rate: Option<f32>,
+    /// Model steps per frame (or tick), each with an equal share of the frame time.
+    #[serde(default = "default_sub_steps")]
+    pub sub_steps: u32,
+    #[serde(with = "rgba")]
+    pub color: Color,
+}
+
+impl<M: ModelUi> Series<M> {
+    pub fn new(model: M, frame_rate: f32, color: Color) -> Self {
+        Self {
+            model,
+            frame_rate,
+            profile: FrameProfile::Constant,
+            seed: 0,
+            trace: FrameTrace::default(),
+            tick_rate: None,
+            sub_steps: 1,
+            color,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let model = SubStepped::new(&self.model, self.sub_steps).label();
+        let label = match self.trace.frame_times() {
+            Some(_) => format!("{model} @ trace"),
+            None => format!("{model} @ {:.0} fps", self.frame_rate),
+        };
+        match self.tick_rate {
+            Some(tick_rate) => format!("{label}, ticks @ {tick_rate:.0} Hz"),
+            None => label,
+        }
+    }
+
+    pub fn simulate(&self, target_duration: f32, start: f32, goal: f32) -> SeriesRun {
+        self.simulate_sub_stepped(self.sub_steps, target_duration, start, goal)
+    }
+
+    fn simulate_sub_stepped(
+        &self,
+        sub_steps: u32,
+        target_duration: f32,
+        start: f32,
+        goal: f32,
+    ) -> SeriesRun {
+        let frame_times: Box<dyn Iterator<Item = f32>> = match self.trace.frame_times() {
+            Some(frame_times) => Box::new(frame_times.iter().copied().cycle()),
+            None => Box::new(self.profile.frame_times(self.frame_rate, self.seed)),
+        };
+        let model = &SubStepped::new(&self.model, sub_steps);
+        let (curve, ticks) = match self.tick_rate {
+            Some(tick_rate) => {
+                let (ticked, interpolated) =
+                    simulate_fixed(model, target_duration, frame_times, tick_rate, start, goal);
+                (interpolated, Some(ticked))
+            }
+            None => (
+                simulate(model, target_duration, frame_times, start, goal),
+                None,
+            ),
+        };
+        SeriesRun {
+            curve,
+            ticks,
+            evaluations: model.evaluations(),
+        }
+    }
+
+    /// Fewest sub-steps with which this series stays within [`MATCH_TOLERANCE`] of `target`.
+    fn sub_steps_to_match(
+        &self,
+        target: &Curve,
+        target_duration: f32,
+        start: f32,
+        goal: f32,
+    ) -> Option<u32> {
+        let scale = (start - goal).abs().max(1e-5);
+        let matches = |sub_steps| {
+            let run = self.simulate_sub_stepped(sub_steps, target_duration, start, goal);
+            Deviation::between_framerates(target, &run.curve, scale).max <= MATCH_TOLERANCE
+        };
+        // Double until it matches, then bisect between the last two counts.
+        let mut high = 1;
+        while !matches(high) {
+            if high >= MAX_SUB_STEPS {
+                return None;
+            }
+            high *= 2;
+        }
+        let mut low = high / 2;
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if matches(middle) {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+        Some(high)
+    }
+
+    /// Returns whether the series should be removed.
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        self.model.ui(ui);
+        ui.add_enabled(
+            self.trace.frame_times().is_none(),
+            Slider::new(&mut self.frame_rate, FRAME_RATES).text("Framerate"),
+        );
+        frame_profile_ui(&mut self.profile, &mut self.seed, ui);
+        self.trace.ui(ui);
+        let mut fixed = self.tick_rate.is_some();
+        ui.checkbox(&mut fixed, "Fixed timestep");
+        self.tick_rate = fixed.then_some(self.tick_rate.unwrap_or(30.0));
+        if let Some(tick_rate) = &mut self.tick_rate {
+            ui.add(Slider::new(tick_rate, TICK_RATES).text("Tick rate"));
+        }
+        ui.add(Slider::new(&mut self.sub_steps, 1..=MAX_SUB_STEPS).text("Sub-steps"));
+        let mut rgba = [self.color.r, self.color.g, self.color.b, self.color.a];
+        let remove = ui
+            .horizontal(|ui| {
+                ui.color_edit_button_rgba_unmultiplied(&mut rgba);
+                ui.button("Remove").clicked()
+            })
+            .inner;
+        self.color = Color::from(rgba);
+        remove
+    }
+}
+
+impl<M: ModelUi> Validate for Series<M> {
+    fn validate(&mut self) -> Result<(), String> {
+        self.model.validate()?;
+        validate::clamp("framerate", &mut self.frame_rate, FRAME_RATES)?;
+        self.profile.validate()?;
+        if let Some(tick_rate) = &mut self.tick_rate {
+            validate::clamp("tick rate", tick_rate, TICK_RATES)?;
+        }
+        validate::clamp("sub-steps", &mut self.sub_steps, 1..=MAX_SUB_STEPS)?;
+        let mut rgba = self.color.into();
+        validate::clamp_color("series colour", &mut rgba)?;
+        self.color = Color::from(rgba);
+        Ok(())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CompareSettings<M> {
+    pub simulating_time: f32,
+    pub series: Vec<Series<M>>,
+    pub show_reference: bool,
+}
+
+impl<M: ModelUi> CompareSettings<M> {
+    /// `model` at 60 and 15 fps.
+    pub fn new(model: &M) -> Self {
+        Self {
+            simulating_time: 2.0,
+            series: vec![
+                Series::new(model.clone(), 60.0, PALETTE[0]),
+                Series::new(model.clone(), 15.0, PALETTE[1]),
+            ],
+            show_reference: true,
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, model: &M) {
+        ui.add(Slider::new(&mut self.simulating_time, SIMULATING_TIMES).text("Sim time"));
+        ui.checkbox(&mut self.show_reference, "Show reference");
+
+        let mut removed = None;
+        for (idx, series) in self.series.iter_mut().enumerate() {
+            egui::CollapsingHeader::new(egui::RichText::new(series.label()).color(
+                egui::Color32::from_rgb(
+                    (series.color.r * 255.0) as u8,
+                    (series.color.g * 255.0) as u8,
+                    (series.color.b * 255.0) as u8,
+                ),
+            ))
+            .id_source(idx)
+            .show(ui, |ui| {
+                if series.ui(ui) {
+                    removed = Some(idx);
+                }
+            });
+        }
+        if let Some(idx) = removed {
+            self.series.remove(idx);
+        }
+        if ui
+            .add_enabled(
+                self.series.len() < MAX_SERIES,
+                egui::Button::new("Add series"),
+            )
+            .clicked()
+        {
+            let mut series = match self.series.last() {
+                Some(last) => last.clone(),
+                None => Series::new(model.clone(), 60.0, PALETTE[0]),
+            };
+            series.color = PALETTE[self.series.len() % PALETTE.len()];
+            self.series.push(series);
+        }
+    }
+}
+
+impl<M: ModelUi> Validate for CompareSettings<M> {
+    fn validate(&mut self) -> Result<(), String> {
+        validate::clamp("sim time", &mut self.simulating_time, SIMULATING_TIMES)?;
+        if self.series.len() > MAX_SERIES {
+            return Err(format!("more than {MAX_SERIES} series"));
+        }
+        self.series.iter_mut().try_for_each(Series::validate)
+    }
+}
+
+/// A stepped series together with the reference it approximates.
+pub struct SeriesResult {
+    pub label: String,
+    pub color: Color,
+    pub curve: Curve,
+    /// Raw values of a fixed-timestep series, which `curve` interpolates between.
+    pub ticks: Option<Curve>,
+    pub reference: Curve,
+    pub to_reference: Deviation,
+    /// Deviation from the first series, at the frame times of the coarser of the two.
+    pub to_first: Deviation,
+    pub evaluations: usize,
+    /// Fewest sub-steps to get within [`MATCH_TOLERANCE`] of the first series, if any up to
+    /// [`MAX_SUB_STEPS`] does. Always `None` for the first series.
+    pub sub_steps_to_match: Option<u32>,
+}
+
+/// Every series of a [`CompareSettings`] and how far they are apart.
+pub struct Comparison {
+    pub series: Vec<SeriesResult>,
+}
+
+impl Comparison {
+    pub fn ui(&self, ui: &mut egui::Ui) {
+        for (idx, result) in self.series.iter().enumerate() {
+            ui.label(format!(
+                "{}: vs reference {}, {} evaluations",
+                idx + 1,
+                result.to_reference,
+                result.evaluations
+            ));
+            if idx > 0 {
+                ui.label(format!("{}: vs 1 {}", idx + 1, result.to_first));
+                ui.label(match result.sub_steps_to_match {
+                    Some(sub_steps) => format!(
+                        "{}: matches 1 within {:.0} % with {sub_steps} sub-steps",
+                        idx + 1,
+                        MATCH_TOLERANCE * 100.0
+                    ),
+                    None => format!(
+                        "{}: doesn't match 1 within {:.0} % with up to {MAX_SUB_STEPS} sub-steps",
+                        idx + 1,
+                        MATCH_TOLERANCE * 100.0
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// Steps every series of `settings` from `start` to `goal`.
+pub fn compare<M: ModelUi>(settings: &CompareSettings<M>, start: f32, goal: f32) -> Comparison {
+    let duration = settings.simulating_time;
+    let scale = (start - goal).abs().max(1e-5);
+    let mut results: Vec<SeriesResult> = Vec::new();
+    for series in &settings.series {
+        let SeriesRun {
+            curve,
+            ticks,
+            evaluations,
+        } = series.simulate(duration, start, goal);
+        let reference = reference(&series.model, duration, start, goal);
+        let (to_first, sub_steps_to_match) = match results.first() {
+            Some(first) => (
+                Deviation::between_framerates(&first.curve, &curve, scale),
+                series.sub_steps_to_match(&first.curve, duration, start, goal),
+            ),
+            None => (Deviation::default(), None),
+        };
+        results.push(SeriesResult {
+            label: series.label(),
+            color: series.color,
+            to_reference: Deviation::between(&curve, &reference, scale),
+            to_first,
+            evaluations,
+            sub_steps_to_match,
+            curve,
+            ticks,
+            reference,
+        });
+    }
+    Comparison { series: results }
+}
+
+pub fn draw_legend(comparison: &Comparison) {
+    let (x, mut y) = (320.0, 60.0);
+    for result in &comparison.series {
+        draw_rectangle(x, y - 10.0, 20.0, 10.0, result.color);
+        draw_text(&result.label, x + 28.0, y, 20.0, WHITE);
+        y += 22.0;
+    }
+}
+
+pub fn draw_reference(curve: &Curve, target_duration: f32, color: Color) {
+    let color = Color { a: 0.5, ..color };
+    draw_polyline(curve, target_duration, color, 1.5, false);
+}
+
+pub fn draw_curve(curve: &Curve, target_duration: f32, color: Color) {
+    draw_polyline(curve, target_duration, color, 3.0, true);
+}
+
+/// Draws `curve` as a staircase, holding each value until the next frame.
+pub fn draw_ticks(curve: &Curve, target_duration: f32, color: Color) {
+    let offset = 300.0;
+    let scale = (screen_width() - offset) / target_duration;
+    let color = Color { a: 0.6, ..color };
+    for idx in 0..curve.values.len() - 1 {
+        let start = offset + scale * curve.times[idx];
+        let end = offset + scale * curve.times[idx + 1];
+        let (value, next) = (curve.values[idx], curve.values[idx + 1]);
+        draw_line(start, value, end, value, 1.5, color);
+        draw_line(end, value, end, next, 1.5, color);
+    }
+}
+
+fn draw_polyline(curve: &Curve, target_duration: f32, color: Color, thickness: f32, markers: bool) {
+    let offset = 300.0;
+    let width = screen_width() - offset;
+
+    let scale = width / target_duration;
+    let values = &curve.values;
+    for idx in 0..values.len() - 1 {
+        let position_start = offset + scale * curve.times[idx];
+        let position_end = offset + scale * curve.times[idx + 1];
+
+        draw_line(
+            position_start,
+            values[idx],
+            position_end,
+            values[idx + 1],
+            thickness,
+            color,
+        );
+        if markers {
+            draw_circle(position_end, values[idx + 1], 6.0, color);
+        }
+    }
+}
+
+/// Draws what a fixed-timestep game would render, newest first as `(frame time, value)`,
+/// on the same time axis as [`draw_history`].
+pub fn draw_rendered(rendered: &VecDeque<(f32, f32)>) {
+    let end = screen_width() * 0.95;
+    let mut age = 0.0;
+    let mut previous: Option<(f32, f32)> = None;
+    for &(dt, value) in rendered {
+        let position = end - age * end;
+        if let Some((previous_position, previous_value)) = previous {
+            draw_line(
+                previous_position,
+                previous_value,
+                position,
+                value,
+                1.5,
+                ORANGE,
+            );
+        }
+        draw_circle(position, value, 3.0, ORANGE);
+        previous = Some((position, value));
+        age += dt;
+    }
+}
+
+pub fn draw_history(history: &VecDeque<f32>, goal: f32, target_dt: f32) {
+    let end = screen_width() * 0.95;
+    let spacing = end / MAX_HISTORY as f32;
+    let spacing_scaled = spacing * (MAX_HISTORY as f32 * target_dt);
+
+    draw_circle(end, goal, 12.0, MAROON);
+
+    for i in 0..history.len() - 1 {
+        let position_start = end - i as f32 * spacing_scaled;
+        let position_end = end - (i + 1) as f32 * spacing_scaled;
+
+        let value_start = history[i];
+        let value_end = history[i + 1];
+
+        draw_line(
+            position_start,
+            value_start,
+            position_end,
+            value_end,
+            2.0,
+            BLUE,
+        );
+        draw_circle(position_start, history[i], 6.0, BLUE);
+    }
+}
@@ -0,0 +1,229 @@
+use std::ops::RangeInclusive;
+
+use egui_macroquad::egui::{self, ComboBox, Slider};
+use serde::{Deserialize, Serialize};
+use spring_it_on::simulation::Model;
+use spring_it_on::smoothing::{Function, SmootherState};
+
+use super::simulation::{ModelUi, FRAME_RATES};
+use super::validate::{self, Validate};
+
+const FACTOR: RangeInclusive<f32> = 0.01..=1.0;
+const DAMPER: RangeInclusive<f32> = 0.01..=20.0;
+/// Wide enough for what the other functions convert to, see [`Functions::equivalents_ui`].
+const HALF_LIFE: RangeInclusive<f32> = 0.01..=100.0;
+const RATE: RangeInclusive<f32> = 0.01..=30.0;
+const SMOOTH_TIME: RangeInclusive<f32> = 0.01..=2.0;
+const MAX_SPEED: RangeInclusive<f32> = 10.0..=5000.0;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Functions {
+    fns: Vec<Function>,
+    selected_index: usize,
+    /// Framerate the equivalent parameters of framerate dependent functions are given at.
+    #[serde(default = "default_reference_fps")]
+    reference_fps: f32,
+}
+
+fn default_reference_fps() -> f32 {
+    60.0
+}
+
+impl Functions {
+    pub fn new() -> Self {
+        Self {
+            fns: vec![
+                Function::Exact,
+                Function::Lerp { factor: 0.5 },
+                Function::DamperBad { damper: 5.0 },
+                Function::DamperExact { half_life: 1.0 },
+                Function::DamperExact2 { rate: 1.0 },
+                Function::SmoothDamp {
+                    smooth_time: 0.3,
+                    max_speed: None,
+                },
+            ],
+            selected_index: 0,
+            reference_fps: default_reference_fps(),
+        }
+    }
+    pub fn current_function(&self) -> &Function {
+        &self.fns[self.selected_index]
+    }
+
+    fn current_function_mut(&mut self) -> &mut Function {
+        &mut self.fns[self.selected_index]
+    }
+
+    /// The other functions tuned to the same half-life as the current one.
+    fn equivalents_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add(Slider::new(&mut self.reference_fps, FRAME_RATES).text("Reference fps"));
+        let fps = self.reference_fps;
+        let half_life = self.current_function().half_life(fps);
+        if !half_life.is_finite() {
+            ui.label("Never gets halfway to the goal");
+            return;
+        }
+        ui.label(format!("Half life {half_life:.3} s at {fps:.0} fps"));
+        for (idx, function) in self.fns.iter().enumerate() {
+            if idx != self.selected_index && !matches!(function, Function::Exact) {
+                ui.label(function.with_half_life(half_life, fps).label());
+            }
+        }
+        let independent = self
+            .fns
+            .iter()
+            .position(|function| matches!(function, Function::DamperExact { .. }));
+        let convertible = !matches!(
+            self.current_function(),
+            Function::Exact | Function::DamperExact { .. }
+        );
+        let button = ui.add_enabled(
+            convertible && independent.is_some(),
+            egui::Button::new("Make framerate independent"),
+        );
+        let clamped = half_life.clamp(*HALF_LIFE.start(), *HALF_LIFE.end());
+        if convertible && clamped != half_life {
+            ui.label(format!(
+                "Damper exact only goes down to a half life of {clamped} s"
+            ));
+        }
+        if let (true, Some(idx)) = (button.clicked(), independent) {
+            self.fns[idx] = Function::DamperExact { half_life: clamped };
+            self.selected_index = idx;
+        }
+    }
+}
+
+impl ModelUi for Function {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        match self {
+            Function::Exact => {}
+            Function::Lerp { factor } => {
+                ui.add(Slider::new(factor, FACTOR).text("Factor"));
+            }
+            Function::DamperBad { damper } => {
+                ui.add(Slider::new(damper, DAMPER).text("Damper"));
+            }
+            Function::DamperExact { half_life } => {
+                ui.add(
+                    Slider::new(half_life, HALF_LIFE)
+                        .logarithmic(true)
+                        .text("Half life"),
+                );
+            }
+            Function::DamperExact2 { rate } => {
+                ui.add(Slider::new(rate, RATE).text("rate"));
+            }
+            Function::SmoothDamp {
+                smooth_time,
+                max_speed,
+            } => {
+                ui.add(Slider::new(smooth_time, SMOOTH_TIME).text("Smooth time"));
+                let mut limited = max_speed.is_some();
+                ui.checkbox(&mut limited, "Limit speed");
+                match (limited, max_speed.as_mut()) {
+                    (true, Some(speed)) => {
+                        ui.add(Slider::new(speed, MAX_SPEED).text("Max speed"));
+                    }
+                    (true, None) => *max_speed = Some(1000.0),
+                    (false, _) => *max_speed = None,
+                }
+            }
+        }
+    }
+}
+
+impl Validate for Function {
+    fn validate(&mut self) -> Result<(), String> {
+        match self {
+            Function::Exact => Ok(()),
+            Function::Lerp { factor } => validate::clamp("factor", factor, FACTOR),
+            Function::DamperBad { damper } => validate::clamp("damper", damper, DAMPER),
+            Function::DamperExact { half_life } => {
+                validate::clamp("half life", half_life, HALF_LIFE)
+            }
+            Function::DamperExact2 { rate } => validate::clamp("rate", rate, RATE),
+            Function::SmoothDamp {
+                smooth_time,
+                max_speed,
+            } => {
+                validate::clamp("smooth time", smooth_time, SMOOTH_TIME)?;
+                match max_speed {
+                    Some(speed) => validate::clamp("max speed", speed, MAX_SPEED),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl Validate for Functions {
+    fn validate(&mut self) -> Result<(), String> {
+        validate::index("function", self.selected_index, self.fns.len())?;
+        validate::clamp("reference fps", &mut self.reference_fps, FRAME_RATES)?;
+        self.fns.iter_mut().try_for_each(Function::validate)
+    }
+}
+
+impl Model for Functions {
+    type State = SmootherState;
+
+    fn label(&self) -> String {
+        self.current_function().label()
+    }
+
+    fn rest(&self, value: f32) -> SmootherState {
+        SmootherState::at(value)
+    }
+
+    fn step(&self, state: &mut SmootherState, goal: f32, dt: f32) -> f32 {
+        Model::step(self.current_function(), state, goal, dt)
+    }
+
+    fn reference(&self, start: f32, goal: f32, t: f32) -> Option<f32> {
+        Model::reference(self.current_function(), start, goal, t)
+    }
+}
+
+impl ModelUi for Functions {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ComboBox::new("Functions", "").show_index(
+            ui,
+            &mut self.selected_index,
+            self.fns.len(),
+            |idx| self.fns[idx].name().to_string(),
+        );
+        self.current_function_mut().ui(ui);
+        ui.collapsing("Equivalent parameters", |ui| self.equivalents_ui(ui));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_life_range_covers_every_slider() {
+        let ends = |range: RangeInclusive<f32>| [*range.start(), *range.end()];
+        for fps in ends(FRAME_RATES) {
+            let functions = ends(FACTOR)
+                .map(|factor| Function::Lerp { factor })
+                .into_iter()
+                .chain(ends(DAMPER).map(|damper| Function::DamperBad { damper }))
+                .chain(ends(RATE).map(|rate| Function::DamperExact2 { rate }))
+                .chain(ends(SMOOTH_TIME).map(|smooth_time| Function::SmoothDamp {
+                    smooth_time,
+                    max_speed: None,
+                }));
+            for function in functions {
+                let half_life = function.half_life(fps);
+                assert!(
+                    half_life <= *HALF_LIFE.end(),
+                    "{} at {fps}",
+                    function.label()
+                );
+            }
+        }
+    }
+}
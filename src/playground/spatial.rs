@@ -0,0 +1,220 @@
+use std::ops::RangeInclusive;
+
+use egui_macroquad::egui::{self, ComboBox, Slider};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use spring_it_on::spatial::{Grid, QueryStats};
+
+use super::page::Page;
+use super::validate::{self, Validate};
+
+const POINT_COUNTS: RangeInclusive<usize> = 100..=10000;
+const CELL_SIZES: RangeInclusive<f32> = 8.0..=200.0;
+const QUERY_SIZES: RangeInclusive<f32> = 10.0..=400.0;
+
+/// Points bouncing around inside the screen.
+pub struct PointCloud {
+    pub positions: Vec<Vec2>,
+    velocities: Vec<Vec2>,
+}
+
+impl PointCloud {
+    pub fn new(count: usize) -> Self {
+        let mut cloud = Self {
+            positions: Vec::new(),
+            velocities: Vec::new(),
+        };
+        cloud.resize(count);
+        cloud
+    }
+
+    pub fn resize(&mut self, count: usize) {
+        self.positions.truncate(count);
+        self.velocities.truncate(count);
+        while self.positions.len() < count {
+            self.positions.push(vec2(
+                rand::gen_range(0.0, screen_width()),
+                rand::gen_range(0.0, screen_height()),
+            ));
+            self.velocities.push(
+                Vec2::from_angle(rand::gen_range(0.0, std::f32::consts::TAU))
+                    * rand::gen_range(10.0, 80.0),
+            );
+        }
+    }
+
+    pub fn update(&mut self, dt: f32, bounds: Vec2) {
+        for (position, velocity) in self.positions.iter_mut().zip(&mut self.velocities) {
+            *position += *velocity * dt;
+            if position.x < 0.0 || position.x > bounds.x {
+                velocity.x = -velocity.x;
+            }
+            if position.y < 0.0 || position.y > bounds.y {
+                velocity.y = -velocity.y;
+            }
+            *position = position.clamp(Vec2::ZERO, bounds);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum QueryShape {
+    Rect,
+    Radius,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SpatialSettings {
+    point_count: usize,
+    cell_size: f32,
+    shape: QueryShape,
+    query_size: f32,
+    show_cells: bool,
+}
+
+impl Default for SpatialSettings {
+    fn default() -> Self {
+        Self {
+            point_count: 3000,
+            cell_size: 40.0,
+            shape: QueryShape::Radius,
+            query_size: 100.0,
+            show_cells: true,
+        }
+    }
+}
+
+impl SpatialSettings {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ComboBox::new("Query", "")
+            .selected_text(match self.shape {
+                QueryShape::Rect => "Range query",
+                QueryShape::Radius => "Radius query",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.shape, QueryShape::Rect, "Range query");
+                ui.selectable_value(&mut self.shape, QueryShape::Radius, "Radius query");
+            });
+        ui.add(Slider::new(&mut self.point_count, POINT_COUNTS).text("Points"));
+        ui.add(Slider::new(&mut self.cell_size, CELL_SIZES).text("Cell size"));
+        ui.add(Slider::new(&mut self.query_size, QUERY_SIZES).text("Query size"));
+        ui.checkbox(&mut self.show_cells, "Show occupied cells");
+    }
+}
+
+impl Validate for SpatialSettings {
+    fn validate(&mut self) -> Result<(), String> {
+        validate::clamp("point count", &mut self.point_count, POINT_COUNTS)?;
+        validate::clamp("cell size", &mut self.cell_size, CELL_SIZES)?;
+        validate::clamp("query size", &mut self.query_size, QUERY_SIZES)
+    }
+}
+
+pub struct SpatialPage {
+    settings: SpatialSettings,
+    points: PointCloud,
+    grid: Grid,
+    hits: Vec<usize>,
+    query_stats: QueryStats,
+}
+
+impl SpatialPage {
+    pub fn new() -> Self {
+        let settings = SpatialSettings::default();
+        Self {
+            points: PointCloud::new(settings.point_count),
+            grid: Grid::new(settings.cell_size),
+            settings,
+            hits: Vec::new(),
+            query_stats: QueryStats::default(),
+        }
+    }
+
+    fn query_center(&self) -> Vec2 {
+        Vec2::from(mouse_position())
+    }
+}
+
+impl Page for SpatialPage {
+    fn name(&self) -> &str {
+        "Spatial data structures"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.settings.ui(ui);
+        ui.label(format!(
+            "Occupied cells {}",
+            self.grid.occupied_cells().count()
+        ));
+        ui.label(format!("Cells visited {}", self.query_stats.cells_visited));
+        ui.label(format!(
+            "Candidates tested {} (brute force {})",
+            self.query_stats.candidates, self.settings.point_count
+        ));
+        ui.label(format!("Hits {}", self.hits.len()));
+    }
+
+    fn save(&self) -> Option<String> {
+        ron::to_string(&self.settings).ok()
+    }
+
+    fn restore(&mut self, saved: &str) -> Result<(), String> {
+        self.settings = validate::from_ron(saved)?;
+        Ok(())
+    }
+
+    fn update(&mut self, dt: f32) {
+        let settings = &self.settings;
+        let bounds = vec2(screen_width(), screen_height());
+        self.points.resize(settings.point_count);
+        self.points.update(dt, bounds);
+        self.grid
+            .rebuild(settings.cell_size, &self.points.positions);
+
+        let center = self.query_center();
+        let extent = Vec2::splat(settings.query_size);
+        let positions = &self.points.positions;
+        self.hits.clear();
+        self.query_stats = match settings.shape {
+            QueryShape::Rect => {
+                self.grid
+                    .query_rect(positions, center - extent, center + extent, &mut self.hits)
+            }
+            QueryShape::Radius => {
+                self.grid
+                    .query_radius(positions, center, settings.query_size, &mut self.hits)
+            }
+        };
+    }
+
+    fn draw(&self) {
+        let settings = &self.settings;
+        if settings.show_cells {
+            let size = self.grid.cell_size();
+            for (x, y) in self.grid.occupied_cells() {
+                draw_rectangle_lines(x as f32 * size, y as f32 * size, size, size, 1.0, DARKGRAY);
+            }
+        }
+        for point in &self.points.positions {
+            draw_rectangle(point.x - 1.0, point.y - 1.0, 2.0, 2.0, GRAY);
+        }
+        for &idx in &self.hits {
+            let point = self.points.positions[idx];
+            draw_circle(point.x, point.y, 3.0, YELLOW);
+        }
+
+        let center = self.query_center();
+        let extent = settings.query_size;
+        match settings.shape {
+            QueryShape::Rect => draw_rectangle_lines(
+                center.x - extent,
+                center.y - extent,
+                extent * 2.0,
+                extent * 2.0,
+                2.0,
+                MAROON,
+            ),
+            QueryShape::Radius => draw_circle_lines(center.x, center.y, extent, 2.0, MAROON),
+        }
+    }
+}
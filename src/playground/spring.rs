@@ -0,0 +1,34 @@
+use std::ops::RangeInclusive;
+
+use egui_macroquad::egui::{self, ComboBox, Slider};
+use spring_it_on::spring::{Integrator, Spring};
+
+use super::simulation::ModelUi;
+use super::validate::{self, Validate};
+
+const STIFFNESS: RangeInclusive<f32> = 1.0..=1000.0;
+const DAMPING: RangeInclusive<f32> = 0.0..=100.0;
+const MASS: RangeInclusive<f32> = 0.1..=10.0;
+
+impl ModelUi for Spring {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ComboBox::new("Integrator", "")
+            .selected_text(self.integrator.name())
+            .show_ui(ui, |ui| {
+                for integrator in Integrator::ALL {
+                    ui.selectable_value(&mut self.integrator, integrator, integrator.name());
+                }
+            });
+        ui.add(Slider::new(&mut self.stiffness, STIFFNESS).text("Stiffness"));
+        ui.add(Slider::new(&mut self.damping, DAMPING).text("Damping"));
+        ui.add(Slider::new(&mut self.mass, MASS).text("Mass"));
+    }
+}
+
+impl Validate for Spring {
+    fn validate(&mut self) -> Result<(), String> {
+        validate::clamp("stiffness", &mut self.stiffness, STIFFNESS)?;
+        validate::clamp("damping", &mut self.damping, DAMPING)?;
+        validate::clamp("mass", &mut self.mass, MASS)
+    }
+}
@@ -0,0 +1,98 @@
+//! Key-value store for state that should survive a restart: a RON file next to the
+//! executable's working directory on native, `localStorage` in the browser.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use std::collections::BTreeMap;
+
+    const PATH: &str = "spring-it-on.ron";
+
+    fn read() -> BTreeMap<String, String> {
+        std::fs::read_to_string(PATH)
+            .ok()
+            .and_then(|text| ron::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get(key: &str) -> Option<String> {
+        read().remove(key)
+    }
+
+    pub fn set(key: &str, value: &str) {
+        let mut entries = read();
+        entries.insert(key.to_string(), value.to_string());
+        if let Ok(text) = ron::ser::to_string_pretty(&entries, Default::default()) {
+            // Losing the saved state isn't worth interrupting the session for.
+            let _ = std::fs::write(PATH, text);
+        }
+    }
+}
+
+/// Implemented by the plugin registered in `index.html`.
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    extern "C" {
+        fn playground_storage_len(key: *const u8, key_len: usize) -> i32;
+        fn playground_storage_read(key: *const u8, key_len: usize, out: *mut u8, out_len: usize);
+        fn playground_storage_write(key: *const u8, key_len: usize, value: *const u8, len: usize);
+    }
+
+    /// Version 0.1.0, packed the way miniquad's `u32_to_semver` unpacks it before comparing
+    /// it with the `version` of the JS plugin. Change both together.
+    #[no_mangle]
+    pub extern "C" fn playground_storage_crate_version() -> u32 {
+        let (major, minor, patch) = (0, 1, 0);
+        (major << 24) | (minor << 16) | patch
+    }
+
+    pub fn get(key: &str) -> Option<String> {
+        let len = unsafe { playground_storage_len(key.as_ptr(), key.len()) };
+        let len = usize::try_from(len).ok()?;
+        let mut bytes = vec![0; len];
+        unsafe { playground_storage_read(key.as_ptr(), key.len(), bytes.as_mut_ptr(), len) };
+        String::from_utf8(bytes).ok()
+    }
+
+    pub fn set(key: &str, value: &str) {
+        unsafe { playground_storage_write(key.as_ptr(), key.len(), value.as_ptr(), value.len()) };
+    }
+}
+
+const PREFIX: &str = "spring-it-on.";
+
+pub fn get(key: &str) -> Option<String> {
+    backend::get(&format!("{PREFIX}{key}"))
+}
+
+pub fn set(key: &str, value: &str) {
+    backend::set(&format!("{PREFIX}{key}"), value)
+}
+
+/// Writes `value` under `key` when it differs from what was written last time, so it can be
+/// called every frame.
+pub struct AutoSave {
+    key: String,
+    last: Option<String>,
+}
+
+impl AutoSave {
+    pub fn new(key: impl Into<String>) -> Self {
+        let key = key.into();
+        Self {
+            last: get(&key),
+            key,
+        }
+    }
+
+    /// What was stored when the session started.
+    pub fn restored(&self) -> Option<&str> {
+        self.last.as_deref()
+    }
+
+    pub fn store(&mut self, value: String) {
+        if self.last.as_ref() != Some(&value) {
+            set(&self.key, &value);
+            self.last = Some(value);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Checks for settings that come from outside the settings window: the autosave, preset files
+//! and permalinks.
+
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use serde::de::DeserializeOwned;
+
+/// Settings that may have been written by something other than their UI.
+pub trait Validate {
+    /// Clamps every value into the range of its slider. Fails when that can't make the
+    /// settings usable, such as for NaN or an index past the end of a list.
+    fn validate(&mut self) -> Result<(), String>;
+}
+
+/// Clamps `value` into `range`, failing for NaN.
+pub fn clamp<T: PartialOrd + Copy + Display>(
+    name: &str,
+    value: &mut T,
+    range: RangeInclusive<T>,
+) -> Result<(), String> {
+    let current = *value;
+    // Only NaN is unordered with itself.
+    if current.partial_cmp(&current).is_none() {
+        return Err(format!("{name} is {current}"));
+    }
+    if current < *range.start() {
+        *value = *range.start();
+    } else if current > *range.end() {
+        *value = *range.end();
+    }
+    Ok(())
+}
+
+/// Clamps every channel of an RGBA colour into `0.0..=1.0`.
+pub fn clamp_color(name: &str, rgba: &mut [f32; 4]) -> Result<(), String> {
+    rgba.iter_mut()
+        .try_for_each(|channel| clamp(name, channel, 0.0..=1.0))
+}
+
+/// Fails unless `index` picks an entry of a non-empty list of `len` entries.
+pub fn index(name: &str, index: usize, len: usize) -> Result<(), String> {
+    if index < len {
+        Ok(())
+    } else {
+        Err(format!("{name} {index} is out of range for {len} entries"))
+    }
+}
+
+/// Parses `text` and validates the result.
+pub fn from_ron<T: DeserializeOwned + Validate>(text: &str) -> Result<T, String> {
+    let mut value: T = ron::from_str(text).map_err(|err| err.to_string())?;
+    value.validate()?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playground::smoothing::Functions;
+
+    #[test]
+    fn clamps_into_range() {
+        let mut value = -3.0;
+        assert_eq!(clamp("value", &mut value, 0.0..=1.0), Ok(()));
+        assert_eq!(value, 0.0);
+        let mut value = f32::INFINITY;
+        assert_eq!(clamp("value", &mut value, 0.0..=1.0), Ok(()));
+        assert_eq!(value, 1.0);
+        let mut count = usize::MAX;
+        assert_eq!(clamp("count", &mut count, 100..=10000), Ok(()));
+        assert_eq!(count, 10000);
+    }
+
+    #[test]
+    fn rejects_nan() {
+        let mut value = f32::NAN;
+        assert!(clamp("value", &mut value, 0.0..=1.0).is_err());
+        assert!(clamp_color("colour", &mut [0.0, f32::NAN, 0.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn rejects_indices_past_the_end() {
+        assert!(index("function", 0, 0).is_err());
+        assert!(index("function", 3, 3).is_err());
+        assert_eq!(index("function", 2, 3), Ok(()));
+    }
+
+    #[test]
+    fn rejects_unusable_functions() {
+        let saved = ron::to_string(&Functions::new()).unwrap();
+        assert!(from_ron::<Functions>(&saved).is_ok());
+        let past_the_end = saved.replace("selected_index:0", "selected_index:6");
+        assert!(from_ron::<Functions>(&past_the_end).is_err());
+        assert!(from_ron::<Functions>("(fns:[],selected_index:0)").is_err());
+        let nan = saved.replace("Lerp(factor:0.5)", "Lerp(factor:NaN)");
+        assert!(from_ron::<Functions>(&nan).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt::Write;
+
+use crate::simulation::{Curve, Model};
+
+/// The inputs of one frame of a Live session.
+#[derive(Clone, Copy)]
+pub struct Frame {
+    pub dt: f32,
+    pub goal: f32,
+}
+
+/// Everything that went into a Live session, so any model can be run against it again.
+#[derive(Clone)]
+pub struct Recording {
+    /// Value the model was resting at when recording started.
+    pub start: f32,
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    pub fn new(start: f32) -> Self {
+        Self {
+            start,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.dt).sum()
+    }
+
+    /// CSV with one `dt,goal` row per frame, preceded by a `# start` comment.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("# start {}\ndt,goal\n", self.start);
+        for frame in &self.frames {
+            writeln!(csv, "{},{}", frame.dt, frame.goal).unwrap();
+        }
+        csv
+    }
+
+    /// Inverse of [`Recording::to_csv`].
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut start = None;
+        let mut frames = Vec::new();
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if let Some(value) = line.strip_prefix("# start") {
+                let value = value
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| {
+                        format!("line {}: invalid start value '{value}'", line_number + 1)
+                    })?;
+                start = Some(value);
+                continue;
+            }
+            let mut columns = line.split(',').map(|column| column.trim().parse::<f32>());
+            let (Some(Ok(dt)), Some(Ok(goal))) = (columns.next(), columns.next()) else {
+                continue;
+            };
+            if !(dt >= 0.0 && dt.is_finite()) {
+                return Err(format!(
+                    "line {}: frame time must not be negative, got {dt}",
+                    line_number + 1
+                ));
+            }
+            if !goal.is_finite() {
+                return Err(format!(
+                    "line {}: goal must be finite, got {goal}",
+                    line_number + 1
+                ));
+            }
+            frames.push(Frame { dt, goal });
+        }
+        if frames.is_empty() {
+            return Err("no frames found".to_string());
+        }
+        Ok(Self {
+            start: start.ok_or("missing '# start' line")?,
+            frames,
+        })
+    }
+}
+
+/// Runs `model` through the recorded frames, starting at rest at the recorded start.
+pub fn replay<M: Model>(model: &M, recording: &Recording) -> Curve {
+    let mut state = model.rest(recording.start);
+    let mut time = 0.0;
+    let mut times = vec![time];
+    let mut values = vec![recording.start];
+    for frame in &recording.frames {
+        time += frame.dt;
+        times.push(time);
+        values.push(model.step(&mut state, frame.goal, frame.dt));
+    }
+    Curve { times, values }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smoothing::Function;
+
+    fn recording() -> Recording {
+        Recording {
+            start: 240.5,
+            frames: vec![
+                Frame {
+                    dt: 1.0 / 60.0,
+                    goal: 100.0,
+                },
+                Frame { dt: 0.0, goal: 0.1 },
+                Frame {
+                    dt: 0.05,
+                    goal: -3.25,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trips_through_csv() {
+        let recording = recording();
+        let parsed = Recording::parse(&recording.to_csv()).unwrap();
+        assert_eq!(parsed.start, recording.start);
+        let frames = |recording: &Recording| -> Vec<(f32, f32)> {
+            recording
+                .frames
+                .iter()
+                .map(|frame| (frame.dt, frame.goal))
+                .collect()
+        };
+        assert_eq!(frames(&parsed), frames(&recording));
+    }
+
+    #[test]
+    fn skips_headers_and_blank_lines() {
+        let parsed = Recording::parse("\n  # start 5 \n# note\ndt, goal\n\n 0.5 , 1\n").unwrap();
+        assert_eq!(parsed.start, 5.0);
+        assert_eq!(parsed.frames.len(), 1);
+        assert_eq!((parsed.frames[0].dt, parsed.frames[0].goal), (0.5, 1.0));
+    }
+
+    #[test]
+    fn rejects_invalid_recordings() {
+        for text in [
+            "",
+            "# start 0\ndt,goal\n",
+            "dt,goal\n0.1,0\n",
+            "# start nope\n0.1,0\n",
+            "# start NaN\n0.1,0\n",
+            "# start 0\n0.1,0\n-0.1,0\n",
+            "# start 0\ninf,0\n",
+            "# start 0\n0.1,NaN\n",
+        ] {
+            assert!(Recording::parse(text).is_err(), "{text:?}");
+        }
+        let Err(err) = Recording::parse("# start 0\n0.1,0\n-0.1,0\n") else {
+            panic!("negative frame time accepted");
+        };
+        assert!(err.starts_with("line 3:"), "{err}");
+    }
+
+    #[test]
+    fn replays_every_frame() {
+        let recording = recording();
+        let curve = replay(&Function::Lerp { factor: 0.5 }, &recording);
+        assert_eq!(curve.values, [240.5, 170.25, 85.175, 40.9625]);
+        assert_eq!(curve.times.len(), 4);
+        assert!((curve.times[3] - recording.duration()).abs() < 1e-6);
+    }
+}
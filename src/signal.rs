@@ -0,0 +1,127 @@
+use std::f32::consts::TAU;
+
+use crate::rng::Rng;
+use crate::smoothing::lerp;
+
+/// Where the goal of a Live simulation comes from.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GoalSignal {
+    /// Set from outside, e.g. by the mouse.
+    Manual,
+    /// Jumps to a new random level every `interval` seconds.
+    Step {
+        interval: f32,
+    },
+    Square {
+        frequency: f32,
+    },
+    Sine {
+        frequency: f32,
+    },
+    /// Sawtooth rising from -1 to 1 once per period.
+    Ramp {
+        frequency: f32,
+    },
+    /// Brownian motion with `speed` units per square-root second, reflected at -1 and 1.
+    RandomWalk {
+        speed: f32,
+    },
+    /// One-dimensional Perlin noise.
+    Noise {
+        frequency: f32,
+    },
+}
+
+impl GoalSignal {
+    pub const ALL: [GoalSignal; 7] = [
+        GoalSignal::Manual,
+        GoalSignal::Step { interval: 1.0 },
+        GoalSignal::Square { frequency: 0.5 },
+        GoalSignal::Sine { frequency: 0.5 },
+        GoalSignal::Ramp { frequency: 0.5 },
+        GoalSignal::RandomWalk { speed: 1.0 },
+        GoalSignal::Noise { frequency: 1.0 },
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            GoalSignal::Manual => "Mouse",
+            GoalSignal::Step { .. } => "Random steps",
+            GoalSignal::Square { .. } => "Square wave",
+            GoalSignal::Sine { .. } => "Sine",
+            GoalSignal::Ramp { .. } => "Ramp",
+            GoalSignal::RandomWalk { .. } => "Random walk",
+            GoalSignal::Noise { .. } => "Perlin noise",
+        }
+    }
+}
+
+/// Value in `-1.0..=1.0` attached to integer `idx`, the same for every call with `seed`.
+fn lattice(seed: u64, idx: i64) -> f32 {
+    let mut rng = Rng::new(seed ^ (idx as u64).wrapping_mul(0xd6e8_feb8_6659_fd93));
+    rng.range(-1.0, 1.0)
+}
+
+fn perlin(seed: u64, x: f32) -> f32 {
+    let cell = x.floor();
+    let offset = x - cell;
+    let left = lattice(seed, cell as i64) * offset;
+    let right = lattice(seed, cell as i64 + 1) * (offset - 1.0);
+    let fade = offset * offset * offset * (offset * (offset * 6.0 - 15.0) + 10.0);
+    // Gradient noise peaks at half the gradient, so stretch it to roughly fill -1..=1.
+    2.0 * lerp(left, right, fade)
+}
+
+/// Plays back a [`GoalSignal`]. The same seed gives the same signal.
+#[derive(Clone)]
+pub struct SignalGenerator {
+    seed: u64,
+    time: f32,
+    walk: f32,
+    rng: Rng,
+}
+
+impl SignalGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            time: 0.0,
+            walk: 0.0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Advances by `dt` and returns the signal in `-1.0..=1.0`, or `None` for
+    /// [`GoalSignal::Manual`].
+    pub fn next(&mut self, signal: &GoalSignal, dt: f32) -> Option<f32> {
+        self.time += dt;
+        let t = self.time;
+        let value = match *signal {
+            GoalSignal::Manual => return None,
+            GoalSignal::Step { interval } => {
+                lattice(self.seed, (t / interval.max(1e-3)).floor() as i64)
+            }
+            GoalSignal::Square { frequency } => {
+                if (t * frequency).fract() < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            GoalSignal::Sine { frequency } => f32::sin(TAU * frequency * t),
+            GoalSignal::Ramp { frequency } => 2.0 * (t * frequency).fract() - 1.0,
+            GoalSignal::RandomWalk { speed } => {
+                // Uniform steps scaled to unit variance.
+                let step = self.rng.range(-1.0, 1.0) * f32::sqrt(3.0 * dt) * speed;
+                self.walk += step;
+                if self.walk.abs() > 1.0 {
+                    self.walk = self.walk.signum() * 2.0 - self.walk;
+                }
+                self.walk.clamp(-1.0, 1.0)
+            }
+            GoalSignal::Noise { frequency } => perlin(self.seed, t * frequency).clamp(-1.0, 1.0),
+        };
+        Some(value)
+    }
+}
@@ -0,0 +1,365 @@
+use std::cell::Cell;
+
+use crate::smoothing::lerp;
+
+/// Rate used to step models without a closed-form solution when computing the reference.
+pub const REFERENCE_RATE: f32 = 2000.0;
+const REFERENCE_SAMPLES: usize = 500;
+
+/// Something that moves a value towards a goal over time.
+pub trait Model {
+    /// Whatever the model has to remember between steps.
+    type State;
+
+    /// Short description including the parameters, used in legends.
+    fn label(&self) -> String;
+    /// State at rest at `value`.
+    fn rest(&self, value: f32) -> Self::State;
+    /// Advances `state` towards `goal` and returns the new value.
+    fn step(&self, state: &mut Self::State, goal: f32, dt: f32) -> f32;
+    /// Value at time `t` of the continuous-time solution starting at rest at `start`,
+    /// for models that have one.
+    fn reference(&self, _start: f32, _goal: f32, _t: f32) -> Option<f32> {
+        None
+    }
+}
+
+/// Runs `model` several times per step with an equal share of `dt`, and counts how often.
+pub struct SubStepped<'a, M> {
+    pub model: &'a M,
+    pub steps: u32,
+    evaluations: Cell<usize>,
+}
+
+impl<'a, M: Model> SubStepped<'a, M> {
+    pub fn new(model: &'a M, steps: u32) -> Self {
+        Self {
+            model,
+            steps: steps.max(1),
+            evaluations: Cell::new(0),
+        }
+    }
+
+    /// Calls to the wrapped model's `step` so far.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.get()
+    }
+}
+
+impl<M: Model> Model for SubStepped<'_, M> {
+    type State = M::State;
+
+    fn label(&self) -> String {
+        match self.steps {
+            1 => self.model.label(),
+            steps => format!("{} x{steps}", self.model.label()),
+        }
+    }
+
+    fn rest(&self, value: f32) -> M::State {
+        self.model.rest(value)
+    }
+
+    fn step(&self, state: &mut M::State, goal: f32, dt: f32) -> f32 {
+        let dt = dt / self.steps as f32;
+        let mut value = 0.0;
+        for _ in 0..self.steps {
+            value = self.model.step(state, goal, dt);
+        }
+        self.evaluations
+            .set(self.evaluations.get() + self.steps as usize);
+        value
+    }
+
+    fn reference(&self, start: f32, goal: f32, t: f32) -> Option<f32> {
+        self.model.reference(start, goal, t)
+    }
+}
+
+/// A discretely stepped trajectory, one value per frame.
+pub struct Curve {
+    pub times: Vec<f32>,
+    pub values: Vec<f32>,
+}
+
+impl Curve {
+    pub fn duration(&self) -> f32 {
+        *self.times.last().unwrap()
+    }
+
+    /// Linearly interpolated value at time `t`, held at the ends.
+    pub fn sample(&self, t: f32) -> f32 {
+        let idx = self.times.partition_point(|&time| time <= t);
+        if idx == 0 {
+            return self.values[0];
+        }
+        match (self.times.get(idx), self.values.get(idx)) {
+            (Some(&end), Some(&b)) => {
+                let (start, a) = (self.times[idx - 1], self.values[idx - 1]);
+                lerp(a, b, (t - start) / (end - start))
+            }
+            _ => *self.values.last().unwrap(),
+        }
+    }
+}
+
+/// How far one curve strays from another, relative to the distance travelled.
+#[derive(Default, Clone, Copy)]
+pub struct Deviation {
+    pub max: f32,
+    pub rms: f32,
+}
+
+impl Deviation {
+    /// Compares `curve` against `other` at the frame times of `curve` that both cover.
+    pub fn between(curve: &Curve, other: &Curve, scale: f32) -> Self {
+        let end = other.duration();
+        let mut max = 0.0f32;
+        let mut sum = 0.0;
+        let mut count = 0;
+        for (&t, &value) in curve.times.iter().zip(&curve.values) {
+            if t > end + 1e-6 {
+                break;
+            }
+            let error = (value - other.sample(t)).abs() / scale;
+            max = max.max(error);
+            sum += error * error;
+            count += 1;
+        }
+        Self {
+            max,
+            rms: f32::sqrt(sum / count.max(1) as f32),
+        }
+    }
+
+    /// Compares two stepped curves at the frame times of the coarser one, so that
+    /// interpolating between its sparse frames doesn't count as error.
+    pub fn between_framerates(a: &Curve, b: &Curve, scale: f32) -> Self {
+        if a.values.len() <= b.values.len() {
+            Self::between(a, b, scale)
+        } else {
+            Self::between(b, a, scale)
+        }
+    }
+}
+
+impl std::fmt::Display for Deviation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "max {:.2} %, rms {:.2} %",
+            self.max * 100.0,
+            self.rms * 100.0
+        )
+    }
+}
+
+/// Steps `model` with the given frame times until `target_duration` has passed. A frame
+/// time that isn't finite and positive ends the curve early, and so does an endless duration.
+pub fn simulate<M: Model>(
+    model: &M,
+    target_duration: f32,
+    frame_times: impl IntoIterator<Item = f32>,
+    start: f32,
+    goal: f32,
+) -> Curve {
+    let mut times = vec![0.0];
+    let mut values = vec![start];
+
+    let mut state = model.rest(start);
+    let mut time = 0.0;
+
+    for dt in frame_times {
+        if !valid_step(dt, target_duration) || time + dt > target_duration + 1e-4 {
+            break;
+        }
+        time += dt;
+        times.push(time);
+        values.push(model.step(&mut state, goal, dt));
+    }
+
+    Curve { times, values }
+}
+
+/// Steps `model` at a fixed `tick_rate` from an accumulator filled by the frame times, like
+/// a game's fixed update. Returns, at every frame, the latest ticked value and the value
+/// interpolated between the last two ticks, which is what the game would render. Stops early
+/// like [`simulate`], and right away when the tick isn't finite and positive.
+pub fn simulate_fixed<M: Model>(
+    model: &M,
+    target_duration: f32,
+    frame_times: impl IntoIterator<Item = f32>,
+    tick_rate: f32,
+    start: f32,
+    goal: f32,
+) -> (Curve, Curve) {
+    let tick = 1.0 / tick_rate;
+    let mut times = vec![0.0];
+    let mut ticked = vec![start];
+    let mut interpolated = vec![start];
+
+    let mut state = model.rest(start);
+    let (mut previous, mut current) = (start, start);
+    let mut accumulator = 0.0;
+    let mut time = 0.0;
+
+    for dt in frame_times {
+        if !valid_step(tick, target_duration)
+            || !valid_step(dt, target_duration)
+            || time + dt > target_duration + 1e-4
+        {
+            break;
+        }
+        time += dt;
+        accumulator += dt;
+        // Tolerate rounding so that a frame of exactly one tick doesn't sometimes miss it.
+        while accumulator >= tick - 1e-6 {
+            accumulator -= tick;
+            previous = current;
+            current = model.step(&mut state, goal, tick);
+        }
+        times.push(time);
+        ticked.push(current);
+        interpolated.push(lerp(
+            previous,
+            current,
+            (accumulator / tick).clamp(0.0, 1.0),
+        ));
+    }
+
+    (
+        Curve {
+            times: times.clone(),
+            values: ticked,
+        },
+        Curve {
+            times,
+            values: interpolated,
+        },
+    )
+}
+
+/// Whether stepping by `dt` makes progress towards a duration that can be reached.
+fn valid_step(dt: f32, target_duration: f32) -> bool {
+    dt > 0.0 && dt.is_finite() && target_duration.is_finite()
+}
+
+/// Ground truth for the stepped curves: the closed-form solution when the model has one,
+/// otherwise the model stepped at [`REFERENCE_RATE`].
+pub fn reference<M: Model>(model: &M, target_duration: f32, start: f32, goal: f32) -> Curve {
+    if model.reference(start, goal, 0.0).is_none() {
+        let frame_times = std::iter::repeat(1.0 / REFERENCE_RATE);
+        return simulate(model, target_duration, frame_times, start, goal);
+    }
+    let times: Vec<f32> = (0..=REFERENCE_SAMPLES)
+        .map(|idx| idx as f32 * target_duration / REFERENCE_SAMPLES as f32)
+        .collect();
+    Curve {
+        values: times
+            .iter()
+            .filter_map(|&t| model.reference(start, goal, t))
+            .collect(),
+        times,
+    }
+}
+
+/// How far `model` stepped at `rate_a` strays from itself stepped at `rate_b` on the way from
+/// `start` to `goal`. Zero for a framerate independent model.
+pub fn framerate_dependence<M: Model>(
+    model: &M,
+    rate_a: f32,
+    rate_b: f32,
+    target_duration: f32,
+    start: f32,
+    goal: f32,
+) -> Deviation {
+    let curve = |rate: f32| {
+        let frame_times = std::iter::repeat(1.0 / rate);
+        simulate(model, target_duration, frame_times, start, goal)
+    };
+    let scale = (start - goal).abs().max(1e-5);
+    Deviation::between_framerates(&curve(rate_a), &curve(rate_b), scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smoothing::Function;
+
+    fn dependence(function: Function) -> Deviation {
+        framerate_dependence(&function, 60.0, 15.0, 2.0, 1.0, 0.0)
+    }
+
+    #[test]
+    fn damper_exact_is_framerate_independent() {
+        let deviation = dependence(Function::DamperExact { half_life: 0.3 });
+        assert!(deviation.max < 1e-4, "{deviation}");
+    }
+
+    #[test]
+    fn damper_bad_depends_on_framerate() {
+        let deviation = dependence(Function::DamperBad { damper: 5.0 });
+        assert!(deviation.max > 0.01, "{deviation}");
+    }
+
+    #[test]
+    fn lerp_depends_on_framerate() {
+        let deviation = dependence(Function::Lerp { factor: 0.1 });
+        assert!(deviation.max > 0.01, "{deviation}");
+    }
+
+    #[test]
+    fn invalid_frame_times_end_the_curve() {
+        let model = Function::DamperExact { half_life: 0.3 };
+        for dt in [0.0, -0.1, f32::NAN, f32::INFINITY] {
+            let curve = simulate(&model, 2.0, std::iter::repeat(dt), 1.0, 0.0);
+            assert_eq!(curve.values, [1.0]);
+        }
+        let curve = simulate(&model, f32::INFINITY, std::iter::repeat(0.1), 1.0, 0.0);
+        assert_eq!(curve.values, [1.0]);
+    }
+
+    #[test]
+    fn invalid_tick_rates_end_the_curve() {
+        let model = Function::DamperExact { half_life: 0.3 };
+        for tick_rate in [0.0, -60.0, f32::NAN, f32::INFINITY] {
+            let frame_times = std::iter::repeat(1.0 / 60.0);
+            let (ticked, _) = simulate_fixed(&model, 2.0, frame_times, tick_rate, 1.0, 0.0);
+            assert_eq!(ticked.values, [1.0]);
+        }
+    }
+
+    #[test]
+    fn fixed_ticks_render_one_tick_behind() {
+        let model = Function::DamperExact { half_life: 0.3 };
+        let tick = 1.0 / 60.0;
+        let ticks = simulate(&model, 2.0, std::iter::repeat(tick), 1.0, 0.0);
+        // The tick curve between its samples, as a game would interpolate it.
+        let at = |t: f32| {
+            let position = (t / tick).max(0.0);
+            let idx = position.floor() as usize;
+            let alpha = position - idx as f32;
+            lerp(ticks.values[idx], ticks.values[idx + 1], alpha)
+        };
+        let frame_patterns: [&[f32]; 3] =
+            [&[1.0 / 30.0], &[1.0 / 120.0], &[1.0 / 50.0, 1.0 / 90.0]];
+        for pattern in frame_patterns {
+            let frame_times = pattern.iter().copied().cycle();
+            let (ticked, interpolated) = simulate_fixed(&model, 1.5, frame_times, 60.0, 1.0, 0.0);
+            assert!(ticked.duration() > 1.4);
+            for (idx, &t) in ticked.times.iter().enumerate() {
+                let latest = ticks.values[((t + 1e-4) / tick).floor() as usize];
+                assert!(
+                    (ticked.values[idx] - latest).abs() < 1e-4,
+                    "{pattern:?} at {t}"
+                );
+                let rendered = at(t - tick);
+                let value = interpolated.values[idx];
+                assert!(
+                    (value - rendered).abs() < 1e-3,
+                    "{pattern:?} at {t}: {value} vs {rendered}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,659 @@
+use std::f32::consts::TAU;
+
+use glam::{Quat, Vec2, Vec3, Vec4};
+
+use crate::color::ColorSpace;
+use crate::simulation::Model;
+
+pub fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from * (1.0 - t) + to * t
+}
+
+/// A value the smoothing functions can move: anything that behaves like a vector of `f32`s.
+pub trait Interpolate: Copy {
+    const ZERO: Self;
+
+    fn add(self, other: Self) -> Self;
+    fn sub(self, other: Self) -> Self;
+    fn scale(self, factor: f32) -> Self;
+    fn dot(self, other: Self) -> f32;
+
+    fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self.add(to.sub(self).scale(t))
+    }
+}
+
+impl Interpolate for f32 {
+    const ZERO: Self = 0.0;
+
+    fn add(self, other: Self) -> Self {
+        self + other
+    }
+    fn sub(self, other: Self) -> Self {
+        self - other
+    }
+    fn scale(self, factor: f32) -> Self {
+        self * factor
+    }
+    fn dot(self, other: Self) -> f32 {
+        self * other
+    }
+    fn lerp(self, to: Self, t: f32) -> Self {
+        lerp(self, to, t)
+    }
+}
+
+macro_rules! impl_interpolate_glam {
+    ($($vec:ty),*) => {
+        $(
+            impl Interpolate for $vec {
+                const ZERO: Self = <$vec>::ZERO;
+
+                fn add(self, other: Self) -> Self {
+                    self + other
+                }
+                fn sub(self, other: Self) -> Self {
+                    self - other
+                }
+                fn scale(self, factor: f32) -> Self {
+                    self * factor
+                }
+                fn dot(self, other: Self) -> f32 {
+                    <$vec>::dot(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_glam!(Vec2, Vec3, Vec4);
+
+/// Component-wise, like a `Vec4`. Only stays a rotation when renormalized, which
+/// [`Function::execute_rotation`] takes care of.
+impl Interpolate for Quat {
+    const ZERO: Self = Quat::from_xyzw(0.0, 0.0, 0.0, 0.0);
+
+    fn add(self, other: Self) -> Self {
+        self + other
+    }
+    fn sub(self, other: Self) -> Self {
+        self - other
+    }
+    fn scale(self, factor: f32) -> Self {
+        self * factor
+    }
+    fn dot(self, other: Self) -> f32 {
+        Quat::dot(self, other)
+    }
+}
+
+/// Smooths all four channels as they are, without any gamma or perceptual correction.
+#[cfg(feature = "macroquad")]
+impl Interpolate for macroquad::color::Color {
+    const ZERO: Self = macroquad::color::Color::new(0.0, 0.0, 0.0, 0.0);
+
+    fn add(self, other: Self) -> Self {
+        to_color(to_vec4(self) + to_vec4(other))
+    }
+    fn sub(self, other: Self) -> Self {
+        to_color(to_vec4(self) - to_vec4(other))
+    }
+    fn scale(self, factor: f32) -> Self {
+        to_color(to_vec4(self) * factor)
+    }
+    fn dot(self, other: Self) -> f32 {
+        to_vec4(self).dot(to_vec4(other))
+    }
+}
+
+#[cfg(feature = "macroquad")]
+fn to_vec4(color: macroquad::color::Color) -> Vec4 {
+    Vec4::new(color.r, color.g, color.b, color.a)
+}
+
+#[cfg(feature = "macroquad")]
+fn to_color(vec: Vec4) -> macroquad::color::Color {
+    macroquad::color::Color::new(vec.x, vec.y, vec.z, vec.w)
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Function {
+    Exact,
+    Lerp {
+        factor: f32,
+    },
+    DamperBad {
+        damper: f32,
+    },
+    DamperExact {
+        half_life: f32,
+    },
+    DamperExact2 {
+        rate: f32,
+    },
+    SmoothDamp {
+        smooth_time: f32,
+        max_speed: Option<f32>,
+    },
+}
+
+/// Per-instance memory of a smoother.
+#[derive(Default, Clone, Copy)]
+pub struct SmootherState<T = f32> {
+    pub value: T,
+    pub velocity: T,
+}
+
+impl<T: Interpolate> SmootherState<T> {
+    pub fn at(value: T) -> Self {
+        Self {
+            value,
+            velocity: T::ZERO,
+        }
+    }
+}
+
+impl Function {
+    /// Moves `state` towards `to` and returns the new value.
+    pub fn execute<T: Interpolate>(&self, state: &mut SmootherState<T>, to: T, dt: f32) -> T {
+        if let Function::SmoothDamp {
+            smooth_time,
+            max_speed,
+        } = *self
+        {
+            return smooth_damp(state, to, smooth_time, max_speed, dt);
+        }
+        let from = state.value;
+        let value = match self.factor(dt) {
+            Some(factor) => from.lerp(to, factor),
+            // Only `SmoothDamp` has no factor, and it returned above.
+            None => to,
+        };
+        if dt > 0.0 {
+            state.velocity = value.sub(from).scale(1.0 / dt);
+        }
+        state.value = value;
+        value
+    }
+
+    /// Share of the remaining distance covered in a step of `dt`. `None` for `SmoothDamp`,
+    /// which carries its velocity over from step to step instead.
+    fn factor(&self, dt: f32) -> Option<f32> {
+        match self {
+            Function::Exact => Some(1.0),
+            Function::Lerp { factor } => Some(*factor),
+            Function::DamperBad { damper } => Some(f32::clamp(damper * dt, 0.0, 1.0)),
+            Function::DamperExact { half_life } => {
+                Some(1.0 - f32::exp(-(f32::ln(2.0) * dt) / (half_life + 1e-5f32)))
+            }
+            Function::DamperExact2 { rate } => Some(1.0 - f32::exp2(-rate * dt)),
+            Function::SmoothDamp { .. } => None,
+        }
+    }
+
+    /// Like [`Function::execute`] for an angle, turning the short way round to `to`. The
+    /// returned angle is wrapped to half a turn either side of zero.
+    pub fn execute_angle(
+        &self,
+        state: &mut SmootherState,
+        to: f32,
+        dt: f32,
+        unit: AngleUnit,
+    ) -> f32 {
+        let to = state.value + unit.wrap(to - state.value);
+        // Shifting by whole turns leaves the velocity as it is.
+        let value = unit.wrap(self.execute(state, to, dt));
+        state.value = value;
+        value
+    }
+
+    /// Like [`Function::execute`] for a rotation, turning the short way round to `to`.
+    /// `SmoothDamp` smooths the four components and renormalizes, every other function
+    /// blends by its factor.
+    pub fn execute_rotation(
+        &self,
+        state: &mut SmootherState<Quat>,
+        to: Quat,
+        dt: f32,
+        blend: RotationBlend,
+    ) -> Quat {
+        // `to` and `-to` are the same rotation, aim for the one in the same hemisphere.
+        let to = if state.value.dot(to) < 0.0 { -to } else { to };
+        let from = state.value;
+        let factor = self.factor(dt);
+        let value = match (factor, blend) {
+            (None, _) => self.execute(state, to, dt),
+            (Some(factor), RotationBlend::Slerp) => from.slerp(to, factor),
+            (Some(factor), RotationBlend::Nlerp) => Quat::lerp(from, to, factor),
+        }
+        .normalize();
+        if dt > 0.0 && factor.is_some() {
+            state.velocity = (value - from) * (1.0 / dt);
+        }
+        state.value = value;
+        value
+    }
+    /// Like [`Function::execute`] for an sRGB colour, smoothed in `space`. `state` holds the
+    /// colour in `space`, start it with [`ColorSpace::from_srgb`]. Returns sRGB.
+    pub fn execute_color(
+        &self,
+        state: &mut SmootherState<Vec4>,
+        to: Vec4,
+        dt: f32,
+        space: ColorSpace,
+    ) -> Vec4 {
+        let to = space.nearest(state.value, space.from_srgb(to));
+        let value = space.wrap(self.execute(state, to, dt));
+        state.value = value;
+        space.to_srgb(value)
+    }
+}
+
+/// How [`Function::execute_angle`] reads its angles.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AngleUnit {
+    Radians,
+    Degrees,
+}
+
+impl AngleUnit {
+    pub fn full_turn(self) -> f32 {
+        match self {
+            AngleUnit::Radians => TAU,
+            AngleUnit::Degrees => 360.0,
+        }
+    }
+
+    /// `angle` shifted by whole turns into the half-open range of half a turn either side
+    /// of zero.
+    pub fn wrap(self, angle: f32) -> f32 {
+        let turn = self.full_turn();
+        (angle + turn / 2.0).rem_euclid(turn) - turn / 2.0
+    }
+}
+
+/// How [`Function::execute_rotation`] moves between two rotations.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RotationBlend {
+    /// Constant angular speed along the arc.
+    Slerp,
+    /// Normalized lerp, cheaper and a little faster in the middle of large turns.
+    Nlerp,
+}
+
+impl Function {
+    /// The continuous-time curve this function approximates, starting at rest at `from`.
+    /// `Lerp` has no such limit since its factor is applied per frame.
+    pub fn reference(&self, from: f32, to: f32, t: f32) -> Option<f32> {
+        let remaining = match self {
+            Function::Exact => {
+                if t > 0.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+            Function::Lerp { .. } => return None,
+            Function::DamperBad { damper } => f32::exp(-damper * t),
+            Function::DamperExact { half_life } => {
+                f32::exp(-(f32::ln(2.0) * t) / (half_life + 1e-5f32))
+            }
+            Function::DamperExact2 { rate } => f32::exp2(-rate * t),
+            Function::SmoothDamp {
+                smooth_time,
+                max_speed: None,
+            } => {
+                let omega = 2.0 / smooth_time.max(1e-4);
+                (1.0 + omega * t) * f32::exp(-omega * t)
+            }
+            Function::SmoothDamp { .. } => return None,
+        };
+        Some(lerp(to, from, remaining))
+    }
+}
+
+/// Half-life of a `SmoothDamp` in units of its smooth time: where `(1 + x) e^-x` is one half,
+/// divided by `omega = 2 / smooth_time`.
+const SMOOTH_DAMP_HALF_LIFE: f32 = 1.678_347 / 2.0;
+
+/// Half-life of a `Lerp` applying `factor` every frame at `frame_rate`. Infinite when the
+/// factor doesn't move at all.
+pub fn lerp_factor_to_half_life(factor: f32, frame_rate: f32) -> f32 {
+    if factor <= 0.0 {
+        return f32::INFINITY;
+    }
+    -f32::ln(2.0) / (frame_rate * f32::ln(1.0 - factor.min(1.0)))
+}
+
+/// Inverse of [`lerp_factor_to_half_life`].
+pub fn half_life_to_lerp_factor(half_life: f32, frame_rate: f32) -> f32 {
+    1.0 - f32::exp2(-1.0 / (half_life * frame_rate))
+}
+
+/// Half-life of a `DamperBad` stepped at `frame_rate`, where it is a `Lerp` by `damper / frame_rate`.
+pub fn damper_to_half_life(damper: f32, frame_rate: f32) -> f32 {
+    lerp_factor_to_half_life(damper / frame_rate, frame_rate)
+}
+
+/// Inverse of [`damper_to_half_life`].
+pub fn half_life_to_damper(half_life: f32, frame_rate: f32) -> f32 {
+    half_life_to_lerp_factor(half_life, frame_rate) * frame_rate
+}
+
+/// Half-life of a `DamperExact2`, which halves the distance every `1 / rate` seconds.
+pub fn rate_to_half_life(rate: f32) -> f32 {
+    1.0 / rate
+}
+
+/// Inverse of [`rate_to_half_life`].
+pub fn half_life_to_rate(half_life: f32) -> f32 {
+    1.0 / half_life
+}
+
+impl Function {
+    /// Time to cover half the way to a fixed goal from rest, when stepped at `frame_rate`.
+    /// Only `Lerp` and `DamperBad` depend on the framerate. `SmoothDamp` ignores its speed
+    /// limit.
+    pub fn half_life(&self, frame_rate: f32) -> f32 {
+        match self {
+            Function::Exact => 0.0,
+            Function::Lerp { factor } => lerp_factor_to_half_life(*factor, frame_rate),
+            Function::DamperBad { damper } => damper_to_half_life(*damper, frame_rate),
+            Function::DamperExact { half_life } => *half_life,
+            Function::DamperExact2 { rate } => rate_to_half_life(*rate),
+            Function::SmoothDamp { smooth_time, .. } => smooth_time * SMOOTH_DAMP_HALF_LIFE,
+        }
+    }
+
+    /// The same kind of function with its parameter set to reach `half_life` at `frame_rate`.
+    pub fn with_half_life(&self, half_life: f32, frame_rate: f32) -> Function {
+        match *self {
+            Function::Exact => Function::Exact,
+            Function::Lerp { .. } => Function::Lerp {
+                factor: half_life_to_lerp_factor(half_life, frame_rate),
+            },
+            Function::DamperBad { .. } => Function::DamperBad {
+                damper: half_life_to_damper(half_life, frame_rate),
+            },
+            Function::DamperExact { .. } => Function::DamperExact { half_life },
+            Function::DamperExact2 { .. } => Function::DamperExact2 {
+                rate: half_life_to_rate(half_life),
+            },
+            Function::SmoothDamp { max_speed, .. } => Function::SmoothDamp {
+                smooth_time: half_life / SMOOTH_DAMP_HALF_LIFE,
+                max_speed,
+            },
+        }
+    }
+}
+
+/// Critically damped spring as in Game Programming Gems 4, chapter 1.10 (Unity's `SmoothDamp`).
+fn smooth_damp<T: Interpolate>(
+    state: &mut SmootherState<T>,
+    to: T,
+    smooth_time: f32,
+    max_speed: Option<f32>,
+    dt: f32,
+) -> T {
+    let smooth_time = smooth_time.max(1e-4);
+    let omega = 2.0 / smooth_time;
+    let x = omega * dt;
+    let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+
+    let max_change = max_speed.map_or(f32::INFINITY, |speed| speed * smooth_time);
+    let mut change = state.value.sub(to);
+    let length = change.length();
+    if length > max_change {
+        change = change.scale(max_change / length);
+    }
+    let target = state.value.sub(change);
+
+    let temp = state.velocity.add(change.scale(omega)).scale(dt);
+    let mut velocity = state.velocity.sub(temp.scale(omega)).scale(exp);
+    let mut value = target.add(change.add(temp).scale(exp));
+
+    // Don't overshoot the original goal.
+    if to.sub(state.value).dot(value.sub(to)) > 0.0 {
+        value = to;
+        velocity = T::ZERO;
+    }
+    state.value = value;
+    state.velocity = velocity;
+    value
+}
+
+impl Default for Function {
+    fn default() -> Self {
+        Self::Lerp { factor: 0.5 }
+    }
+}
+
+impl Function {
+    pub fn name(&self) -> &str {
+        match self {
+            Function::Exact => "Exact",
+            Function::Lerp { .. } => "Lerp",
+            Function::DamperBad { .. } => "DamperBad",
+            Function::DamperExact { .. } => "Damper Exact",
+            Function::DamperExact2 { .. } => "Damper Exact 2",
+            Function::SmoothDamp { .. } => "Smooth Damp",
+        }
+    }
+}
+
+impl Model for Function {
+    type State = SmootherState;
+
+    fn label(&self) -> String {
+        match self {
+            Function::Exact => self.name().to_string(),
+            Function::Lerp { factor } => format!("{} {factor:.2}", self.name()),
+            Function::DamperBad { damper } => format!("{} {damper:.2}", self.name()),
+            Function::DamperExact { half_life } => {
+                format!("{} half life {half_life:.2}", self.name())
+            }
+            Function::DamperExact2 { rate } => format!("{} rate {rate:.2}", self.name()),
+            Function::SmoothDamp {
+                smooth_time,
+                max_speed,
+            } => match max_speed {
+                Some(speed) => format!("{} {smooth_time:.2} max {speed:.0}", self.name()),
+                None => format!("{} {smooth_time:.2}", self.name()),
+            },
+        }
+    }
+
+    fn rest(&self, value: f32) -> SmootherState {
+        SmootherState::at(value)
+    }
+
+    fn step(&self, state: &mut SmootherState, goal: f32, dt: f32) -> f32 {
+        self.execute(state, goal, dt)
+    }
+
+    fn reference(&self, start: f32, goal: f32, t: f32) -> Option<f32> {
+        Function::reference(self, start, goal, t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn functions() -> [Function; 6] {
+        [
+            Function::Exact,
+            Function::Lerp { factor: 0.5 },
+            Function::DamperBad { damper: 5.0 },
+            Function::DamperExact { half_life: 0.1 },
+            Function::DamperExact2 { rate: 10.0 },
+            Function::SmoothDamp {
+                smooth_time: 0.2,
+                max_speed: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn only_smooth_damp_has_no_factor() {
+        for function in functions() {
+            let is_smooth_damp = matches!(function, Function::SmoothDamp { .. });
+            assert_eq!(function.factor(1.0 / 60.0).is_none(), is_smooth_damp);
+        }
+    }
+
+    #[test]
+    fn turns_the_short_way_across_half_a_turn() {
+        for (unit, from, to) in [
+            (AngleUnit::Radians, 3.0, -3.0),
+            (AngleUnit::Degrees, 170.0, -170.0),
+        ] {
+            let half_turn = unit.full_turn() / 2.0;
+            let end = to + unit.full_turn();
+            for function in functions() {
+                let name = function.name();
+                let mut state = SmootherState::at(from);
+                // The angle without wrapping, which should climb from `from` through the half
+                // turn to `to` one turn up.
+                let mut unwrapped = from;
+                for _ in 0..600 {
+                    let value = function.execute_angle(&mut state, to, 1.0 / 60.0, unit);
+                    assert!((-half_turn..half_turn).contains(&value), "{name}: {value}");
+                    let next = unwrapped + unit.wrap(value - unwrapped);
+                    assert!(next >= unwrapped - 1e-4, "{name} turned back: {next}");
+                    assert!(next <= end + 1e-3, "{name} overshot: {next}");
+                    unwrapped = next;
+                }
+                assert!(
+                    (unwrapped - end).abs() < 1e-2,
+                    "{name} ended at {unwrapped}"
+                );
+                assert!(
+                    (state.value - to).abs() < 1e-2,
+                    "{name} ended at {}",
+                    state.value
+                );
+            }
+        }
+    }
+
+    fn assert_relative(actual: f32, expected: f32, what: &str) {
+        let error = (actual - expected).abs() / expected.abs().max(1e-6);
+        assert!(error < 1e-3, "{what}: {actual} instead of {expected}");
+    }
+
+    #[test]
+    fn half_life_conversions_invert_each_other() {
+        for frame_rate in [10.0, 60.0, 240.0] {
+            for factor in [0.01, 0.1, 0.5, 0.9] {
+                let half_life = lerp_factor_to_half_life(factor, frame_rate);
+                let back = half_life_to_lerp_factor(half_life, frame_rate);
+                assert_relative(back, factor, &format!("factor at {frame_rate}"));
+            }
+            for damper in [0.1, 1.0, 5.0] {
+                let half_life = damper_to_half_life(damper, frame_rate);
+                let back = half_life_to_damper(half_life, frame_rate);
+                assert_relative(back, damper, &format!("damper at {frame_rate}"));
+            }
+        }
+        for rate in [0.01, 1.0, 30.0] {
+            assert_relative(half_life_to_rate(rate_to_half_life(rate)), rate, "rate");
+        }
+    }
+
+    #[test]
+    fn lerp_half_life_has_no_sign_flip() {
+        assert_eq!(lerp_factor_to_half_life(0.0, 60.0), f32::INFINITY);
+        assert_eq!(lerp_factor_to_half_life(-0.5, 60.0), f32::INFINITY);
+        assert_eq!(lerp_factor_to_half_life(1.0, 60.0), 0.0);
+        assert_eq!(damper_to_half_life(0.0, 60.0), f32::INFINITY);
+    }
+
+    #[test]
+    fn with_half_life_round_trips() {
+        for frame_rate in [15.0, 60.0, 144.0] {
+            for half_life in [0.05, 0.3, 2.0] {
+                for function in functions().iter().skip(1) {
+                    let tuned = function.with_half_life(half_life, frame_rate);
+                    let what = format!("{} at {frame_rate}", tuned.label());
+                    assert_relative(tuned.half_life(frame_rate), half_life, &what);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lerp_gets_halfway_in_its_half_life() {
+        let function = Function::Lerp {
+            factor: half_life_to_lerp_factor(0.5, 60.0),
+        };
+        let mut state = SmootherState::at(1.0);
+        let mut value = 1.0;
+        for _ in 0..30 {
+            value = function.execute(&mut state, 0.0, 1.0 / 60.0);
+        }
+        assert!((value - 0.5).abs() < 1e-4, "{value}");
+    }
+
+    #[test]
+    fn rotates_with_every_function() {
+        let to = Quat::from_rotation_z(1.0);
+        for function in functions() {
+            for blend in [RotationBlend::Slerp, RotationBlend::Nlerp] {
+                let mut state = SmootherState::at(Quat::IDENTITY);
+                let value = function.execute_rotation(&mut state, to, 1.0 / 60.0, blend);
+                assert!(value.is_normalized(), "{}", function.name());
+                assert!(value.angle_between(to) < 1.0, "{}", function.name());
+            }
+        }
+    }
+
+    /// Values of `function` stepping from `from` to `to` at 60 fps for ten seconds.
+    fn run(function: &Function, from: f32, to: f32) -> Vec<f32> {
+        let mut state = SmootherState::at(from);
+        (0..600)
+            .map(|_| function.execute(&mut state, to, 1.0 / 60.0))
+            .collect()
+    }
+
+    #[test]
+    fn smooth_damp_converges_without_overshooting() {
+        for smooth_time in [0.05, 0.3, 1.0] {
+            let function = Function::SmoothDamp {
+                smooth_time,
+                max_speed: None,
+            };
+            let values = run(&function, 0.0, 1.0);
+            assert!(
+                values.windows(2).all(|pair| pair[0] <= pair[1]),
+                "{values:?}"
+            );
+            assert!(values.iter().all(|&value| value <= 1.0), "{values:?}");
+            assert!(1.0 - values[values.len() - 1] < 1e-3, "{values:?}");
+        }
+    }
+
+    #[test]
+    fn smooth_damp_respects_max_speed() {
+        let function = Function::SmoothDamp {
+            smooth_time: 0.3,
+            max_speed: Some(50.0),
+        };
+        let values = run(&function, 0.0, 100.0);
+        let fastest = values
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]) * 60.0)
+            .fold(0.0, f32::max);
+        assert!(fastest <= 50.0, "{fastest}");
+        // Unlimited, it would be most of the way there within a second.
+        assert!(values[59] < 60.0, "{}", values[59]);
+        assert!(100.0 - values[values.len() - 1] < 1.0);
+    }
+}
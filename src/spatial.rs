@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+
+/// Uniform grid bucketing point indices by the cell they fall into.
+pub struct Grid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+/// How much work a query did, to compare against a brute-force scan.
+#[derive(Default, Clone, Copy)]
+pub struct QueryStats {
+    pub cells_visited: usize,
+    pub candidates: usize,
+}
+
+fn check_cell_size(cell_size: f32) {
+    assert!(
+        cell_size > 0.0 && cell_size.is_finite(),
+        "cell size must be positive and finite, got {cell_size}"
+    );
+}
+
+impl Grid {
+    /// Panics unless `cell_size` is positive and finite.
+    pub fn new(cell_size: f32) -> Self {
+        check_cell_size(cell_size);
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    fn cell(&self, position: Vec2) -> (i32, i32) {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Clears the grid and inserts every point, keyed by its index. Panics unless `cell_size`
+    /// is positive and finite.
+    pub fn rebuild(&mut self, cell_size: f32, points: &[Vec2]) {
+        check_cell_size(cell_size);
+        self.cell_size = cell_size;
+        self.cells.clear();
+        for (idx, &point) in points.iter().enumerate() {
+            self.cells.entry(self.cell(point)).or_default().push(idx);
+        }
+    }
+
+    pub fn occupied_cells(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// Calls `visit` for every point index stored in a cell overlapping `min..max`.
+    fn visit_cells(&self, min: Vec2, max: Vec2, mut visit: impl FnMut(usize)) -> QueryStats {
+        let (min_x, min_y) = self.cell(min);
+        let (max_x, max_y) = self.cell(max);
+        let mut stats = QueryStats::default();
+        let span = |min: i32, max: i32| (i64::from(max) - i64::from(min) + 1).max(0) as u64;
+        // A query much larger than the cells would loop over up to 2^64 of them, checking
+        // the occupied ones is quicker.
+        if span(min_x, max_x).saturating_mul(span(min_y, max_y)) > self.cells.len() as u64 {
+            for (&(x, y), indices) in &self.cells {
+                stats.cells_visited += 1;
+                if (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y) {
+                    stats.candidates += indices.len();
+                    indices.iter().copied().for_each(&mut visit);
+                }
+            }
+            return stats;
+        }
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                stats.cells_visited += 1;
+                if let Some(indices) = self.cells.get(&(x, y)) {
+                    stats.candidates += indices.len();
+                    indices.iter().copied().for_each(&mut visit);
+                }
+            }
+        }
+        stats
+    }
+
+    /// Pushes the indices of all points inside the rectangle `min..max` into `hits`.
+    pub fn query_rect(
+        &self,
+        points: &[Vec2],
+        min: Vec2,
+        max: Vec2,
+        hits: &mut Vec<usize>,
+    ) -> QueryStats {
+        self.visit_cells(min, max, |idx| {
+            let p = points[idx];
+            if p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y {
+                hits.push(idx);
+            }
+        })
+    }
+
+    /// Pushes the indices of all points within `radius` of `center` into `hits`.
+    pub fn query_radius(
+        &self,
+        points: &[Vec2],
+        center: Vec2,
+        radius: f32,
+        hits: &mut Vec<usize>,
+    ) -> QueryStats {
+        let extent = Vec2::splat(radius);
+        self.visit_cells(center - extent, center + extent, |idx| {
+            if points[idx].distance_squared(center) <= radius * radius {
+                hits.push(idx);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    fn points() -> Vec<Vec2> {
+        let mut rng = Rng::new(7);
+        (0..500)
+            .map(|_| Vec2::new(rng.range(-300.0, 300.0), rng.range(-300.0, 300.0)))
+            .collect()
+    }
+
+    fn brute_force(points: &[Vec2], inside: impl Fn(Vec2) -> bool) -> Vec<usize> {
+        (0..points.len())
+            .filter(|&idx| inside(points[idx]))
+            .collect()
+    }
+
+    #[test]
+    fn queries_match_a_brute_force_scan() {
+        let points = points();
+        let mut rng = Rng::new(11);
+        for cell_size in [5.0, 37.5, 1000.0] {
+            let mut grid = Grid::new(1.0);
+            grid.rebuild(cell_size, &points);
+            for _ in 0..50 {
+                let center = Vec2::new(rng.range(-350.0, 350.0), rng.range(-350.0, 350.0));
+                let extent = Vec2::new(rng.range(0.0, 200.0), rng.range(0.0, 200.0));
+                let (min, max) = (center - extent, center + extent);
+                let mut hits = Vec::new();
+                grid.query_rect(&points, min, max, &mut hits);
+                hits.sort_unstable();
+                let expected = brute_force(&points, |p| p.cmpge(min).all() && p.cmple(max).all());
+                assert_eq!(hits, expected, "rect {min}..{max} in cells of {cell_size}");
+
+                let radius = extent.x;
+                let mut hits = Vec::new();
+                grid.query_radius(&points, center, radius, &mut hits);
+                hits.sort_unstable();
+                let expected = brute_force(&points, |p| p.distance(center) <= radius);
+                assert_eq!(
+                    hits, expected,
+                    "radius {radius} at {center} in cells of {cell_size}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn huge_queries_only_visit_occupied_cells() {
+        let points = points();
+        let mut grid = Grid::new(1e-3);
+        grid.rebuild(1e-3, &points);
+        let mut hits = Vec::new();
+        let stats = grid.query_radius(&points, Vec2::ZERO, f32::INFINITY, &mut hits);
+        assert_eq!(hits.len(), points.len());
+        assert_eq!(stats.cells_visited, grid.occupied_cells().count());
+    }
+
+    #[test]
+    #[should_panic(expected = "cell size")]
+    fn rejects_invalid_cell_sizes() {
+        Grid::new(1.0).rebuild(f32::NAN, &points());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::simulation::Model;
+
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Integrator {
+    ExplicitEuler,
+    SemiImplicitEuler,
+    Exact,
+}
+
+impl Integrator {
+    pub const ALL: [Integrator; 3] = [
+        Integrator::ExplicitEuler,
+        Integrator::SemiImplicitEuler,
+        Integrator::Exact,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Integrator::ExplicitEuler => "Explicit Euler",
+            Integrator::SemiImplicitEuler => "Semi-implicit Euler",
+            Integrator::Exact => "Exact",
+        }
+    }
+}
+
+/// A damped harmonic oscillator pulling a value towards a goal.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+    pub integrator: Integrator,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self {
+            stiffness: 100.0,
+            damping: 10.0,
+            mass: 1.0,
+            integrator: Integrator::SemiImplicitEuler,
+        }
+    }
+}
+
+impl Spring {
+    fn acceleration(&self, position: f32, velocity: f32, goal: f32) -> f32 {
+        (-self.stiffness * (position - goal) - self.damping * velocity) / self.mass
+    }
+
+    /// Advances `position` and `velocity` by `dt` and returns the new pair.
+    pub fn advance(&self, position: f32, velocity: f32, goal: f32, dt: f32) -> (f32, f32) {
+        match self.integrator {
+            Integrator::ExplicitEuler => {
+                let acceleration = self.acceleration(position, velocity, goal);
+                (position + velocity * dt, velocity + acceleration * dt)
+            }
+            Integrator::SemiImplicitEuler => {
+                let velocity = velocity + self.acceleration(position, velocity, goal) * dt;
+                (position + velocity * dt, velocity)
+            }
+            Integrator::Exact => self.exact(position, velocity, goal, dt),
+        }
+    }
+
+    /// Closed-form solution of `m x'' = -k (x - goal) - c x'`.
+    fn exact(&self, position: f32, velocity: f32, goal: f32, dt: f32) -> (f32, f32) {
+        let mass = self.mass.max(1e-5);
+        let omega = f32::sqrt(self.stiffness.max(0.0) / mass);
+        if omega < 1e-5 {
+            return (position + velocity * dt, velocity);
+        }
+        let zeta = self.damping / (2.0 * mass * omega);
+        let y0 = position - goal;
+        let v0 = velocity;
+
+        if (zeta - 1.0).abs() < 1e-4 {
+            let c = v0 + omega * y0;
+            let decay = f32::exp(-omega * dt);
+            (goal + decay * (y0 + c * dt), decay * (v0 - omega * c * dt))
+        } else if zeta < 1.0 {
+            let a = zeta * omega;
+            let omega_d = omega * f32::sqrt(1.0 - zeta * zeta);
+            let b = (v0 + a * y0) / omega_d;
+            let decay = f32::exp(-a * dt);
+            let (sin, cos) = f32::sin_cos(omega_d * dt);
+            (
+                goal + decay * (y0 * cos + b * sin),
+                decay * (v0 * cos - (a * b + y0 * omega_d) * sin),
+            )
+        } else {
+            let root = f32::sqrt(zeta * zeta - 1.0);
+            let r1 = -omega * (zeta - root);
+            let r2 = -omega * (zeta + root);
+            let c2 = (v0 - r1 * y0) / (r2 - r1);
+            let c1 = y0 - c2;
+            let (e1, e2) = (f32::exp(r1 * dt), f32::exp(r2 * dt));
+            (goal + c1 * e1 + c2 * e2, c1 * r1 * e1 + c2 * r2 * e2)
+        }
+    }
+}
+
+impl Model for Spring {
+    /// Position and velocity.
+    type State = (f32, f32);
+
+    fn label(&self) -> String {
+        format!(
+            "{} k {:.0} c {:.1} m {:.1}",
+            self.integrator.name(),
+            self.stiffness,
+            self.damping,
+            self.mass
+        )
+    }
+
+    fn rest(&self, value: f32) -> (f32, f32) {
+        (value, 0.0)
+    }
+
+    fn step(&self, state: &mut (f32, f32), goal: f32, dt: f32) -> f32 {
+        *state = self.advance(state.0, state.1, goal, dt);
+        state.0
+    }
+
+    fn reference(&self, start: f32, goal: f32, t: f32) -> Option<f32> {
+        Some(self.exact(start, 0.0, goal, t).0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::framerate_dependence;
+
+    /// Under-, critically and over-damped.
+    fn springs() -> [Spring; 3] {
+        [2.0, 20.0, 50.0].map(|damping| Spring {
+            stiffness: 100.0,
+            damping,
+            mass: 1.0,
+            integrator: Integrator::Exact,
+        })
+    }
+
+    /// Position after `t` from RK4 steps of 0.1 ms, in f64 so rounding doesn't add up.
+    fn integrate(spring: &Spring, position: f64, velocity: f64, goal: f64, t: f64) -> f64 {
+        let (k, c, m) = (
+            spring.stiffness as f64,
+            spring.damping as f64,
+            spring.mass as f64,
+        );
+        let derivative = |(x, v): (f64, f64)| (v, (-k * (x - goal) - c * v) / m);
+        let dt = 1e-4;
+        let mut state = (position, velocity);
+        for _ in 0..(t / dt).round() as u32 {
+            let add = |(x, v): (f64, f64), (dx, dv): (f64, f64), h: f64| (x + dx * h, v + dv * h);
+            let k1 = derivative(state);
+            let k2 = derivative(add(state, k1, dt / 2.0));
+            let k3 = derivative(add(state, k2, dt / 2.0));
+            let k4 = derivative(add(state, k3, dt));
+            state.0 += dt / 6.0 * (k1.0 + 2.0 * k2.0 + 2.0 * k3.0 + k4.0);
+            state.1 += dt / 6.0 * (k1.1 + 2.0 * k2.1 + 2.0 * k3.1 + k4.1);
+        }
+        state.0
+    }
+
+    #[test]
+    fn exact_matches_fine_integration() {
+        for spring in springs() {
+            for t in [0.05, 0.3, 1.0] {
+                let (exact, _) = spring.advance(1.0, 3.0, 0.25, t);
+                let integrated = integrate(&spring, 1.0, 3.0, 0.25, t as f64) as f32;
+                assert!(
+                    (exact - integrated).abs() < 1e-4,
+                    "damping {} at {t}: {exact} vs {integrated}",
+                    spring.damping
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn exact_is_framerate_independent() {
+        for spring in springs() {
+            let deviation = framerate_dependence(&spring, 60.0, 15.0, 2.0, 1.0, 0.0);
+            assert!(
+                deviation.max < 1e-4,
+                "damping {}: {deviation}",
+                spring.damping
+            );
+        }
+    }
+
+    #[test]
+    fn euler_is_framerate_dependent() {
+        let spring = Spring {
+            integrator: Integrator::SemiImplicitEuler,
+            ..springs()[0].clone()
+        };
+        let deviation = framerate_dependence(&spring, 60.0, 15.0, 2.0, 1.0, 0.0);
+        assert!(deviation.max > 0.01, "{deviation}");
+    }
+}
@@ -0,0 +1,346 @@
+use std::fmt::Write;
+
+use crate::simulation::Curve;
+
+const MARGIN_LEFT: f32 = 70.0;
+const MARGIN_RIGHT: f32 = 20.0;
+const MARGIN_TOP: f32 = 20.0;
+const MARGIN_BOTTOM: f32 = 50.0;
+const LEGEND_ROW: f32 = 18.0;
+const TICKS: f32 = 6.0;
+
+/// How a line is stroked.
+#[derive(Clone, Copy)]
+pub struct Stroke {
+    pub width: f32,
+    pub opacity: f32,
+    /// Draw a dot at every sample, to show where the frames are.
+    pub markers: bool,
+}
+
+impl Stroke {
+    /// Thick line with a dot per frame, like the stepped curves in the playground.
+    pub const FRAMES: Stroke = Stroke {
+        width: 2.0,
+        opacity: 1.0,
+        markers: true,
+    };
+    /// Thin translucent line, like the references in the playground.
+    pub const REFERENCE: Stroke = Stroke {
+        width: 1.0,
+        opacity: 0.5,
+        markers: false,
+    };
+}
+
+struct Line {
+    label: String,
+    color: [f32; 4],
+    stroke: Stroke,
+    points: Vec<(f32, f32)>,
+}
+
+/// A time/value chart with axes and a legend, rendered to an SVG document.
+pub struct Plot {
+    width: f32,
+    height: f32,
+    x_label: String,
+    y_label: String,
+    lines: Vec<Line>,
+}
+
+impl Plot {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            x_label: "time (s)".to_string(),
+            y_label: "value".to_string(),
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn x_label(mut self, label: impl Into<String>) -> Self {
+        self.x_label = label.into();
+        self
+    }
+
+    pub fn y_label(mut self, label: impl Into<String>) -> Self {
+        self.y_label = label.into();
+        self
+    }
+
+    /// Adds `curve` to the chart. `color` is RGBA in `0.0..=1.0`.
+    pub fn curve(
+        &mut self,
+        label: impl Into<String>,
+        color: [f32; 4],
+        stroke: Stroke,
+        curve: &Curve,
+    ) {
+        self.lines.push(Line {
+            label: label.into(),
+            color,
+            stroke,
+            points: curve
+                .times
+                .iter()
+                .copied()
+                .zip(curve.values.iter().copied())
+                .collect(),
+        });
+    }
+
+    /// Range covered by all lines along one axis, padded when it is empty.
+    fn bounds(&self, axis: impl Fn(&(f32, f32)) -> f32) -> (f32, f32) {
+        let (min, max) = self
+            .lines
+            .iter()
+            .flat_map(|line| line.points.iter().map(&axis))
+            .filter(|value| value.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+        if min > max {
+            (0.0, 1.0)
+        } else if max - min < 1e-6 {
+            (min - 0.5, max + 0.5)
+        } else {
+            (min, max)
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let (x_min, x_max) = self.bounds(|&(x, _)| x);
+        let (y_min, y_max) = self.bounds(|&(_, y)| y);
+        let legend_height = LEGEND_ROW * self.lines.len() as f32;
+        let left = MARGIN_LEFT;
+        let right = self.width - MARGIN_RIGHT;
+        let top = MARGIN_TOP + legend_height;
+        let bottom = self.height - MARGIN_BOTTOM;
+        let to_x = |x: f32| left + (x - x_min) / (x_max - x_min) * (right - left);
+        let to_y = |y: f32| bottom - (y - y_min) / (y_max - y_min) * (bottom - top);
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+            w = self.width,
+            h = self.height
+        )
+        .unwrap();
+        writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+
+        // Grid lines and tick labels.
+        for x in ticks(x_min, x_max) {
+            let px = to_x(x);
+            writeln!(
+                svg,
+                r##"<line x1="{px:.2}" y1="{top:.2}" x2="{px:.2}" y2="{bottom:.2}" stroke="#ddd"/>"##
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{px:.2}" y="{:.2}" text-anchor="middle">{}</text>"#,
+                bottom + 16.0,
+                tick_label(x)
+            )
+            .unwrap();
+        }
+        for y in ticks(y_min, y_max) {
+            let py = to_y(y);
+            writeln!(
+                svg,
+                r##"<line x1="{left:.2}" y1="{py:.2}" x2="{right:.2}" y2="{py:.2}" stroke="#ddd"/>"##
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}" text-anchor="end">{}</text>"#,
+                left - 6.0,
+                py + 4.0,
+                tick_label(y)
+            )
+            .unwrap();
+        }
+
+        // Axes and their labels.
+        writeln!(
+            svg,
+            r#"<polyline points="{left:.2},{top:.2} {left:.2},{bottom:.2} {right:.2},{bottom:.2}" fill="none" stroke="black"/>"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{:.2}" text-anchor="middle">{}</text>"#,
+            (left + right) / 2.0,
+            self.height - 12.0,
+            escape(&self.x_label)
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text transform="translate(16 {:.2}) rotate(-90)" text-anchor="middle">{}</text>"#,
+            (top + bottom) / 2.0,
+            escape(&self.y_label)
+        )
+        .unwrap();
+
+        for line in &self.lines {
+            let color = rgb(line.color);
+            let opacity = line.color[3] * line.stroke.opacity;
+            let points: Vec<String> = line
+                .points
+                .iter()
+                .filter(|(x, y)| x.is_finite() && y.is_finite())
+                .map(|&(x, y)| format!("{:.2},{:.2}", to_x(x), to_y(y)))
+                .collect();
+            writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{color}" stroke-opacity="{opacity:.2}" stroke-width="{}"/>"#,
+                points.join(" "),
+                line.stroke.width
+            )
+            .unwrap();
+            if line.stroke.markers {
+                writeln!(svg, r#"<g fill="{color}" fill-opacity="{opacity:.2}">"#).unwrap();
+                for point in &points {
+                    let (x, y) = point.split_once(',').unwrap();
+                    writeln!(svg, r#"<circle cx="{x}" cy="{y}" r="2.5"/>"#).unwrap();
+                }
+                writeln!(svg, "</g>").unwrap();
+            }
+        }
+
+        // Legend above the chart, one row per line.
+        for (idx, line) in self.lines.iter().enumerate() {
+            let y = MARGIN_TOP + idx as f32 * LEGEND_ROW;
+            writeln!(
+                svg,
+                r#"<rect x="{left:.2}" y="{:.2}" width="20" height="8" fill="{}" fill-opacity="{:.2}"/>"#,
+                y - 4.0,
+                rgb(line.color),
+                line.color[3] * line.stroke.opacity
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}">{}</text>"#,
+                left + 28.0,
+                y + 4.0,
+                escape(&line.label)
+            )
+            .unwrap();
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Evenly spaced round values covering `min..=max`.
+fn ticks(min: f32, max: f32) -> impl Iterator<Item = f32> {
+    let rough = (max - min) / TICKS;
+    let magnitude = 10f32.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|&step| step >= rough)
+        .unwrap_or(rough);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(move |idx| idx as f32 * step)
+}
+
+fn tick_label(value: f32) -> String {
+    // Avoid printing rounding noise such as 0.30000001.
+    let label = format!("{:.3}", value);
+    let label = label.trim_end_matches('0').trim_end_matches('.');
+    if label == "-0" {
+        "0".to_string()
+    } else {
+        label.to_string()
+    }
+}
+
+fn rgb(color: [f32; 4]) -> String {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(color[0]),
+        channel(color[1]),
+        channel(color[2])
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(values: &[f32]) -> Curve {
+        Curve {
+            times: (0..values.len()).map(|idx| idx as f32).collect(),
+            values: values.to_vec(),
+        }
+    }
+
+    #[test]
+    fn renders_lines_legend_and_markers() {
+        let mut plot = Plot::new(400.0, 300.0).y_label("height <px>");
+        plot.curve(
+            "a & b",
+            [1.0, 0.5, 0.0, 1.0],
+            Stroke::FRAMES,
+            &curve(&[0.0, 1.0, 0.5]),
+        );
+        plot.curve(
+            "reference",
+            [0.0; 4],
+            Stroke::REFERENCE,
+            &curve(&[0.0, 1.0]),
+        );
+        let svg = plot.render();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(r##"stroke="#ff8000""##));
+        assert!(svg.contains(">a &amp; b</text>"));
+        assert!(svg.contains(">height &lt;px&gt;</text>"));
+        // Markers for the three frames of the first line only.
+        assert_eq!(svg.matches("<circle").count(), 3);
+        // The line starts in the bottom left corner of the chart and peaks at its top.
+        let top = MARGIN_TOP + 2.0 * LEGEND_ROW;
+        let bottom = 300.0 - MARGIN_BOTTOM;
+        let start = format!("{MARGIN_LEFT:.2},{bottom:.2}");
+        assert!(svg.contains(&format!(r#"<polyline points="{start} "#)));
+        assert!(svg.contains(&format!(",{top:.2} ")));
+    }
+
+    #[test]
+    fn skips_non_finite_points() {
+        let mut plot = Plot::new(400.0, 300.0);
+        plot.curve("", [0.0; 4], Stroke::FRAMES, &curve(&[0.0, f32::NAN, 1.0]));
+        let svg = plot.render();
+        assert!(!svg.contains("NaN"));
+        assert_eq!(svg.matches("<circle").count(), 2);
+    }
+
+    #[test]
+    fn renders_an_empty_plot() {
+        let svg = Plot::new(400.0, 300.0).render();
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(!svg.contains("inf") && !svg.contains("NaN"));
+    }
+
+    #[test]
+    fn ticks_are_round() {
+        let ticks: Vec<String> = ticks(-0.13, 1.07).map(tick_label).collect();
+        assert_eq!(ticks, ["0", "0.2", "0.4", "0.6", "0.8", "1"]);
+    }
+}